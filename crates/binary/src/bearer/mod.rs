@@ -0,0 +1,61 @@
+use prettytable::{row, Cell, Table};
+use struch::modemmanager::bearer::BearerInfo;
+
+use crate::format::{object_id, or_dash};
+
+pub fn list(bearers: &[BearerInfo]) {
+    let mut tab = Table::new();
+    tab.add_row(row![
+        Frb->"Id",
+        Fgb->"Interface",
+        Fgb->"APN",
+        Fgb->"IP Type",
+        Fgb->"Address",
+        Fyb->"Connected",
+    ]);
+    for bearer_info in bearers {
+        let bearer = &bearer_info.bearer;
+        let mut row = row![
+            Fr->object_id(&bearer.dbus_path),
+            Fg->or_dash(&bearer.status.interface),
+            Fg->or_dash(&bearer.properties.apn),
+            Fg->or_dash(&bearer.properties.ip_type),
+            Fg->or_dash(&bearer.ipv4_config.address),
+        ];
+        if bearer_info.is_connected() {
+            row.add_cell(Cell::new("yes").style_spec("Fg"));
+        } else {
+            row.add_cell(Cell::new("no").style_spec("Fr"));
+        }
+        tab.add_row(row);
+    }
+    tab.printstd();
+}
+
+pub fn show(bearer_info: &BearerInfo) {
+    let bearer = &bearer_info.bearer;
+    let properties = &bearer.properties;
+    println!("Bearer:            {}", bearer.dbus_path);
+    println!("Connected:         {}", bearer.status.connected);
+    println!("Interface:         {}", or_dash(&bearer.status.interface));
+    println!("APN:               {}", or_dash(&properties.apn));
+    println!("APN type:          {}", or_dash(&properties.apn_type));
+    println!("IP type:           {}", or_dash(&properties.ip_type));
+    println!("User:              {}", or_dash(&properties.user));
+    println!("Profile id:        {}", or_dash(&properties.profile_id));
+    println!(
+        "Roaming allowance: {}",
+        or_dash(&properties.roaming_allowance)
+    );
+    println!("Multiplexed:       {}", or_dash(&bearer.status.multiplexed));
+    println!(
+        "IPv4:              {}",
+        or_dash(&bearer.ipv4_config.address)
+    );
+    if !bearer.status.connection_error.name.is_empty() {
+        println!(
+            "Error:             {} ({})",
+            bearer.status.connection_error.message, bearer.status.connection_error.name
+        );
+    }
+}
@@ -1,12 +1,27 @@
-use std::io;
+use std::{fs, io, thread, time::Duration};
 
-use binary::{device, modem};
-use struch::modemmanager::bearer::BearerInfo;
+use binary::{
+    bearer, call, device, location, modem, operator, profile,
+    query::{Fields, Query},
+    signal, sim, sms,
+};
+use struch::ipconfig;
+use struch::modemmanager::bearer::{Auth, BearerInfo, BearerProperties, IpType};
+use struch::modemmanager::call::{CallInfo, CallState};
 use struch::modemmanager::device::ModemDevice;
+use struch::modemmanager::location::LocationSource;
+use struch::modemmanager::modes::{Band, Mode, ModeCombination};
+use struch::modemmanager::packet::PacketServiceState;
+use struch::modemmanager::power::PowerState;
+use struch::modemmanager::profile::Profile as ModemProfile;
+use struch::modemmanager::sms::SmsInfo;
+use struch::modemmanager::ussd::{Balance, UssdState};
+use struch::nftables::Share as NatShare;
+use struch::provider::ProviderDatabase;
 use struch::{iproute2::Device, modemmanager::device::ModemDeviceList};
 
-use clap::Parser;
-use struch::modemmanager::modem::{ModemInfo, PortList};
+use clap::{Args, Parser, Subcommand};
+use struch::modemmanager::modem::ModemInfo;
 
 #[derive(Parser)]
 #[clap(about = "A simple tool to manage net device")]
@@ -17,6 +32,30 @@ pub enum Command {
     ListModem(ModemFilter),
     #[clap(about = "Auto connect lte")]
     Lte(Lte),
+    #[clap(about = "Share the lte uplink with lan devices")]
+    Share(Share),
+    #[clap(about = "Manage sms messages")]
+    Sms(Sms),
+    #[clap(about = "Manage voice calls")]
+    Call(Call),
+    #[clap(about = "Run ussd sessions")]
+    Ussd(Ussd),
+    #[clap(about = "Show the modem location")]
+    Location(Location),
+    #[clap(about = "Show extended signal metrics")]
+    Signal(Signal),
+    #[clap(about = "Manage the sim card")]
+    Sim(Sim),
+    #[clap(about = "Configure the modem")]
+    Modem(Modem),
+    #[clap(about = "Scan networks and choose the operator")]
+    Operator(Operator),
+    #[clap(about = "Manage the modem bearers")]
+    Bearer(Bearer),
+    #[clap(about = "Manage the connection profiles stored in the modem")]
+    Profile(Profile),
+    #[clap(about = "Show the network time")]
+    Time(Time),
 }
 
 #[derive(Parser)]
@@ -27,8 +66,494 @@ pub struct Lte {
     connect: bool,
     #[clap(short, long, default_value = "0")]
     modem_id: u32,
-    #[clap(short, long, default_value = "cmnet")]
-    apn: String,
+    /// Connect this existing bearer instead of the properties below
+    #[clap(short, long)]
+    bearer: Option<String>,
+    #[clap(flatten)]
+    bearer_args: BearerArgs,
+    /// Seconds to wait for packet service attach before connecting
+    #[clap(long)]
+    attach_timeout: Option<u64>,
+    /// SIM PIN used to unlock the modem before connecting
+    #[clap(long)]
+    pin: Option<String>,
+    /// File holding the SIM PIN
+    #[clap(long)]
+    pin_file: Option<String>,
+}
+
+#[derive(Args)]
+pub struct BearerArgs {
+    /// APN, looked up from the SIM unless a profile id is given, left to the
+    /// network when the provider is unknown
+    #[clap(short, long)]
+    apn: Option<String>,
+    /// ipv4, ipv6 or ipv4v6
+    #[clap(long)]
+    ip_type: Option<IpType>,
+    #[clap(long)]
+    user: Option<String>,
+    #[clap(long)]
+    password: Option<String>,
+    /// Allowed authentication methods, e.g. pap,chap
+    #[clap(long, value_delimiter = ',')]
+    auth: Vec<Auth>,
+    /// Allow connecting while roaming
+    #[clap(long)]
+    allow_roaming: Option<bool>,
+    /// Roaming allowance, e.g. home|partner
+    #[clap(long)]
+    roaming_allowance: Option<String>,
+    /// APN type, e.g. default, ims or mms
+    #[clap(long)]
+    apn_type: Option<String>,
+    /// Connect with a profile stored in the modem, see `profile list`
+    #[clap(long)]
+    profile_id: Option<u32>,
+    /// none, requested or required
+    #[clap(long)]
+    multiplex: Option<String>,
+}
+
+impl BearerArgs {
+    fn properties(&self, modem_info: &ModemInfo) -> BearerProperties {
+        let base = match (&self.apn, self.profile_id) {
+            (Some(apn), _) => BearerProperties::new(apn),
+            // a stored profile carries its own APN
+            (None, Some(_)) => BearerProperties::new(""),
+            (None, None) => provider_apn(modem_info),
+        };
+        let allowed_auth = match self.auth.is_empty() {
+            true => base.allowed_auth.clone(),
+            false => self.auth.clone(),
+        };
+        BearerProperties {
+            ip_type: self.ip_type,
+            user: self.user.clone().unwrap_or_else(|| base.user.clone()),
+            password: self
+                .password
+                .clone()
+                .unwrap_or_else(|| base.password.clone()),
+            allowed_auth,
+            allow_roaming: self.allow_roaming,
+            roaming_allowance: self.roaming_allowance.clone().unwrap_or_default(),
+            apn_type: self.apn_type.clone().unwrap_or_default(),
+            profile_id: self.profile_id,
+            multiplex: self.multiplex.clone().unwrap_or_default(),
+            ..base
+        }
+    }
+}
+
+/// Look up the APN of the SIM home network in the provider database,
+/// falling back to the serving operator, e.g. for SIMs without an
+/// operator code. An unknown provider leaves the APN to the network.
+fn provider_apn(modem_info: &ModemInfo) -> BearerProperties {
+    let (sim_operator, imsi) = match modem_info.sim() {
+        Ok(Some(sim_info)) => (
+            sim_info.sim.properties.operator_code,
+            sim_info.sim.properties.imsi,
+        ),
+        _ => (String::new(), String::new()),
+    };
+    let operator_code = &modem_info.modem.n3gpp.operator_code;
+    let found = ProviderDatabase::load().ok().and_then(|db| {
+        db.lookup_operator(&sim_operator)
+            .map(|settings| (settings.clone(), format!("SIM operator {}", sim_operator)))
+            .or_else(|| {
+                let settings = db.lookup_imsi(&imsi)?;
+                Some((
+                    settings.clone(),
+                    format!("IMSI {}", &imsi[..imsi.len().min(6)]),
+                ))
+            })
+            .or_else(|| {
+                let settings = db.lookup_operator(operator_code)?;
+                Some((settings.clone(), format!("operator {}", operator_code)))
+            })
+    });
+    match found {
+        Some((settings, source)) => {
+            println!(
+                "Using APN {} of {} for {}",
+                settings.apn, settings.provider, source
+            );
+            settings.properties()
+        }
+        None => {
+            println!("No provider found for the SIM, leaving the APN to the network");
+            BearerProperties::new("")
+        }
+    }
+}
+
+#[derive(Parser)]
+pub struct Share {
+    /// Lan device name, can be repeated
+    #[clap(short, long, required_unless_present = "stop")]
+    from: Vec<String>,
+    /// Modem device id of the uplink
+    #[clap(short, long, default_value = "0")]
+    to: u32,
+    /// Remove the sharing rules
+    #[clap(short, long, default_value = "false")]
+    stop: bool,
+}
+
+#[derive(Parser)]
+pub struct Sms {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: SmsAction,
+}
+
+#[derive(Subcommand)]
+pub enum SmsAction {
+    #[clap(about = "List all messages")]
+    List,
+    #[clap(about = "Read a message")]
+    Read { sms_id: String },
+    #[clap(about = "Send a text message")]
+    Send {
+        #[clap(short, long)]
+        number: String,
+        text: String,
+        /// Keep a copy in the SIM card
+        #[clap(short, long, default_value = "false")]
+        store: bool,
+    },
+    #[clap(about = "Delete a message")]
+    Delete { sms_id: String },
+    #[clap(about = "Store a message in the SIM card")]
+    Store { sms_id: String },
+}
+
+#[derive(Parser)]
+pub struct Call {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: CallAction,
+}
+
+#[derive(Subcommand)]
+pub enum CallAction {
+    #[clap(about = "List all calls")]
+    List,
+    #[clap(about = "Show a call")]
+    Show { call_id: String },
+    #[clap(about = "Dial a number")]
+    Dial {
+        number: String,
+        /// Follow the call state until it is answered or ends
+        #[clap(short, long, default_value = "false")]
+        wait: bool,
+        #[clap(short, long, default_value = "60")]
+        timeout: u64,
+    },
+    #[clap(about = "Answer an incoming call")]
+    Accept { call_id: String },
+    #[clap(about = "Hang up a call, or all calls when no id is given")]
+    Hangup { call_id: Option<String> },
+    #[clap(about = "Send DTMF tones on an active call")]
+    Dtmf { call_id: String, tones: String },
+    #[clap(about = "Delete a call")]
+    Delete { call_id: String },
+}
+
+#[derive(Parser)]
+pub struct Ussd {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: UssdAction,
+}
+
+#[derive(Subcommand)]
+pub enum UssdAction {
+    #[clap(about = "Start a session, e.g. *100#")]
+    Initiate {
+        code: String,
+        /// Extract balance and remaining data from the reply
+        #[clap(short, long, default_value = "false")]
+        parse: bool,
+    },
+    #[clap(about = "Answer a network menu")]
+    Respond { response: String },
+    #[clap(about = "Cancel the session")]
+    Cancel,
+    #[clap(about = "Show the session state")]
+    Status,
+}
+
+#[derive(Parser)]
+pub struct Location {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    /// Enable the GPS raw and NMEA sources
+    #[clap(short, long, default_value = "false")]
+    gps: bool,
+    /// Enable assisted GPS
+    #[clap(short, long, default_value = "false")]
+    agps: bool,
+    /// Keep printing location updates
+    #[clap(short, long, default_value = "false")]
+    follow: bool,
+    /// Seconds between updates in follow mode, at least 1
+    #[clap(short, long, default_value = "5", value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+}
+
+#[derive(Parser)]
+pub struct Signal {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    /// Seconds between signal measurements, at least 1
+    #[clap(short, long, default_value = "5", value_parser = clap::value_parser!(u32).range(1..))]
+    rate: u32,
+    /// Keep printing measurements
+    #[clap(short, long, default_value = "false")]
+    follow: bool,
+}
+
+#[derive(Parser)]
+pub struct Sim {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: SimAction,
+}
+
+#[derive(Subcommand)]
+pub enum SimAction {
+    #[clap(about = "Show sim details and remaining unlock retries")]
+    Info,
+    #[clap(about = "Unlock with the PIN")]
+    Pin { pin: String },
+    #[clap(about = "Unblock with the PUK and set a new PIN")]
+    Puk { puk: String, new_pin: String },
+    #[clap(about = "Change the PIN")]
+    ChangePin { old_pin: String, new_pin: String },
+    #[clap(about = "Require the PIN on power up")]
+    EnablePin { pin: String },
+    #[clap(about = "Do not require the PIN on power up")]
+    DisablePin { pin: String },
+    #[clap(about = "List the sim slots")]
+    Slots,
+    #[clap(about = "Make another slot the primary one")]
+    Switch {
+        slot: u32,
+        /// Seconds to wait for the modem to come back
+        #[clap(short, long, default_value = "60")]
+        timeout: u64,
+    },
+}
+
+#[derive(Parser)]
+pub struct Modem {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: ModemAction,
+}
+
+#[derive(Subcommand)]
+pub enum ModemAction {
+    #[clap(about = "Show or set allowed modes and bands")]
+    Config {
+        /// Allowed modes, e.g. 4g or 4g,5g
+        #[clap(short, long, value_delimiter = ',')]
+        allowed: Vec<Mode>,
+        /// Preferred mode among the allowed ones
+        #[clap(short, long)]
+        preferred: Option<Mode>,
+        /// Current bands, e.g. eutran-3,eutran-8 or any
+        #[clap(short, long, value_delimiter = ',')]
+        bands: Vec<Band>,
+    },
+    #[clap(about = "Enable the modem")]
+    Enable {
+        /// Seconds to wait for the state change
+        #[clap(short, long, default_value = "30")]
+        timeout: u64,
+    },
+    #[clap(about = "Disable the modem")]
+    Disable {
+        /// Seconds to wait for the state change
+        #[clap(short, long, default_value = "30")]
+        timeout: u64,
+    },
+    #[clap(about = "Attach to the packet service")]
+    Attach {
+        /// Seconds to wait for the attach
+        #[clap(short, long, default_value = "30")]
+        timeout: u64,
+    },
+    #[clap(about = "Detach from the packet service")]
+    Detach,
+    #[clap(about = "Show or set the power state: on, low or off")]
+    Power {
+        state: Option<PowerState>,
+        /// Seconds to wait for the state change
+        #[clap(short, long, default_value = "30")]
+        timeout: u64,
+    },
+    #[clap(about = "List or select firmware images")]
+    Firmware {
+        /// Unique id of the image to boot
+        #[clap(short, long)]
+        select: Option<String>,
+        /// Seconds to wait for the modem to come back
+        #[clap(short, long, default_value = "120")]
+        timeout: u64,
+    },
+    #[clap(about = "List or select carrier configurations")]
+    Carrier {
+        /// Id of the configuration to activate
+        #[clap(short, long)]
+        select: Option<String>,
+        /// Seconds to wait for the modem to come back
+        #[clap(short, long, default_value = "120")]
+        timeout: u64,
+    },
+    #[clap(about = "Show or set the initial EPS bearer used to attach to LTE")]
+    Eps {
+        #[clap(short, long)]
+        apn: Option<String>,
+        /// ipv4, ipv6 or ipv4v6
+        #[clap(long)]
+        ip_type: Option<IpType>,
+        #[clap(long)]
+        user: Option<String>,
+        #[clap(long)]
+        password: Option<String>,
+        /// Allowed authentication methods, e.g. pap,chap
+        #[clap(long, value_delimiter = ',')]
+        auth: Vec<Auth>,
+    },
+    #[clap(about = "Show the 5G mode or set the 5G registration settings")]
+    Nr5g {
+        /// disabled or enabled
+        #[clap(long)]
+        mico_mode: Option<String>,
+        /// 32, 64, 128 or 256
+        #[clap(long)]
+        drx_cycle: Option<String>,
+    },
+    #[clap(about = "Print modem, bearer, signal and sms events as they happen")]
+    Watch {
+        /// Seconds between two polls of the modem, at least 1
+        #[clap(short, long, default_value = "5", value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    #[clap(about = "Reset the modem")]
+    Reset {
+        /// Reset to factory settings with this service programming code
+        #[clap(long)]
+        factory: Option<String>,
+        /// Seconds to wait for the modem to come back
+        #[clap(short, long, default_value = "60")]
+        timeout: u64,
+    },
+}
+
+#[derive(Parser)]
+pub struct Operator {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: OperatorAction,
+}
+
+#[derive(Subcommand)]
+pub enum OperatorAction {
+    #[clap(about = "Scan for available networks, takes minutes")]
+    Scan {
+        /// Seconds to wait for the scan to finish
+        #[clap(short, long, default_value = "300")]
+        timeout: u64,
+    },
+    #[clap(about = "Register in a network by MCC and MNC, e.g. 46000")]
+    Register { code: String },
+    #[clap(about = "Return to automatic network selection")]
+    Auto,
+}
+
+#[derive(Parser)]
+pub struct Bearer {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: BearerAction,
+}
+
+#[derive(Subcommand)]
+pub enum BearerAction {
+    #[clap(about = "List the bearers of the modem")]
+    List,
+    #[clap(about = "Show a bearer")]
+    Show { bearer_id: String },
+    #[clap(about = "Create a bearer without connecting it")]
+    Create(BearerArgs),
+    #[clap(about = "Connect a bearer")]
+    Connect { bearer_id: String },
+    #[clap(about = "Disconnect a bearer")]
+    Disconnect { bearer_id: String },
+    #[clap(about = "Delete a bearer")]
+    Delete { bearer_id: String },
+    #[clap(about = "Delete all disconnected bearers")]
+    Prune,
+}
+
+#[derive(Parser)]
+pub struct Profile {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    #[clap(subcommand)]
+    action: ProfileAction,
+}
+
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    #[clap(about = "List the stored profiles")]
+    List,
+    #[clap(about = "Create a profile, or update it when an id is given")]
+    Set {
+        #[clap(short, long)]
+        id: Option<u32>,
+        #[clap(short, long)]
+        name: Option<String>,
+        #[clap(short, long)]
+        apn: Option<String>,
+        /// APN type, e.g. default, ims or mms
+        #[clap(long)]
+        apn_type: Option<String>,
+        /// ipv4, ipv6 or ipv4v6
+        #[clap(long)]
+        ip_type: Option<IpType>,
+        #[clap(long)]
+        user: Option<String>,
+        #[clap(long)]
+        password: Option<String>,
+        /// Allowed authentication methods, e.g. pap,chap
+        #[clap(long, value_delimiter = ',')]
+        auth: Vec<Auth>,
+    },
+    #[clap(about = "Delete a profile")]
+    Delete { id: u32 },
+}
+
+#[derive(Parser)]
+pub struct Time {
+    #[clap(short, long, default_value = "0")]
+    modem_id: u32,
+    /// Set the system clock from the network time
+    #[clap(short, long, default_value = "false")]
+    sync: bool,
+    /// Only set the clock when it is off by more seconds than this
+    #[clap(short, long, default_value = "5")]
+    threshold: u64,
 }
 
 #[derive(Parser)]
@@ -39,6 +564,13 @@ pub struct DeviceFilter {
     name: Option<String>,
     #[clap(short, long)]
     link_type: Option<String>,
+
+    /// Extra hardware columns, comma separated
+    #[clap(short, long, value_delimiter = ',')]
+    columns: Vec<device::Column>,
+
+    /// Filter expression, e.g. 'operstate=UP and name~^wwan and has-ipv4'
+    query: Option<String>,
 }
 
 #[derive(Parser)]
@@ -52,120 +584,651 @@ pub struct ModemFilter {
     name: Option<String>,
 
     /// Modem connect status:
-    /// connected or disconnected
+    /// connected, disconnected or a modem state such as registered
     #[clap(short, long)]
     state: Option<String>,
+
+    /// Filter expression, e.g. 'connected and signal>=50 and access-tech=lte'
+    query: Option<String>,
 }
 
 fn main() -> io::Result<()> {
     let cmd = Command::parse();
     match cmd {
         Command::ListModem(filter) => {
-            let filter = move |device: &ModemDevice| {
-                if let Some(id) = filter.id {
-                    if device.device_id != id {
-                        return false;
+            let state = filter.state.as_ref().map(|state| match state.as_str() {
+                "connected" => Query::equals("connected", true),
+                "disconnected" => Query::equals("connected", false),
+                state => Query::equals("state", state),
+            });
+            let query = Query::all([
+                parse_query::<ModemDevice>(&filter.query)?,
+                filter.id.map(|id| Query::equals("id", id)),
+                filter.name.as_ref().map(|name| Query::equals("name", name)),
+                state,
+            ]);
+            modem::info(move |device| query.as_ref().is_none_or(|q| q.matches(device)))?
+        }
+        Command::ListDevice(filter) => {
+            let query = Query::all([
+                parse_query::<Device>(&filter.query)?,
+                filter.id.map(|id| Query::equals("id", id)),
+                filter.name.as_ref().map(|name| Query::equals("name", name)),
+                filter
+                    .link_type
+                    .as_ref()
+                    .map(|link_type| Query::equals("type", link_type)),
+            ]);
+            device::info(
+                move |device| query.as_ref().is_none_or(|q| q.matches(device)),
+                &filter.columns,
+            )?
+        }
+        Command::Lte(lte) => {
+            if lte.connect && lte.disconnect {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Can't connect and disconnect at the same time",
+                ));
+            }
+            check_root()?;
+            let modem_id = modem_path(lte.modem_id)?;
+            if lte.connect {
+                let mut modem_info = ModemInfo::new(&modem_id)?;
+                if modem_info.modem.generic.state == "locked" {
+                    let pin = match (&lte.pin, &lte.pin_file) {
+                        (Some(pin), _) => pin.clone(),
+                        (None, Some(file)) => fs::read_to_string(file)?.trim().to_string(),
+                        (None, None) => {
+                            sim::retries(&modem_info);
+                            return Err(io::Error::new(
+                                io::ErrorKind::PermissionDenied,
+                                "SIM is locked, pass --pin or --pin-file",
+                            ));
+                        }
+                    };
+                    modem_info.unlock(&pin)?;
+                }
+                if let Some(timeout) = lte.attach_timeout {
+                    modem_info.wait_attached(Duration::from_secs(timeout))?;
+                }
+                let bearer_info = match &lte.bearer {
+                    Some(bearer_id) => {
+                        let bearer_info = BearerInfo::new(bearer_id)?;
+                        if !bearer_info.is_connected() {
+                            bearer_info.connect()?;
+                        }
+                        BearerInfo::new(bearer_id)?
+                    }
+                    None => {
+                        if modem_info.modem.generic.state != "connected" {
+                            modem_info.connect_with(&lte.bearer_args.properties(&modem_info))?;
+                        }
+                        modem_info.refresh()?;
+                        modem_info.connected_bearer()?.ok_or_else(|| {
+                            io::Error::new(io::ErrorKind::NotConnected, "No connected bearer")
+                        })?
                     }
+                };
+                ipconfig::bring_up(&modem_info, &bearer_info)?;
+                println!("sucess");
+            }
+            if lte.disconnect {
+                ipconfig::tear_down(&ModemInfo::new(modem_id)?)?;
+                println!("sucess");
+            }
+        }
+        Command::Share(share) => {
+            check_root()?;
+            let modem_info = ModemInfo::new(modem_path(share.to)?)?;
+            let bearer_info = modem_info.connected_bearer()?;
+            let device_name = bearer_info
+                .as_ref()
+                .and_then(ipconfig::uplink)
+                .unwrap_or_else(|| modem_info.net_device_name());
+            if share.stop {
+                NatShare::stop(&device_name)?;
+                println!("sucess");
+                return Ok(());
+            }
+            let bearer_info = bearer_info.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    format!("Modem {} is not connected", share.to),
+                )
+            })?;
+            let mut nat = NatShare::new(&share.from, &device_name);
+            if let Some(mtu) = bearer_info.bearer.ipv4_config.mtu() {
+                nat = nat.mtu(mtu);
+            }
+            nat.start()?;
+            println!("sucess");
+        }
+        Command::Sms(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            match cmd.action {
+                SmsAction::List => sms::list(&modem_info.list_sms()?)?,
+                SmsAction::Read { sms_id } => sms::show(&SmsInfo::new(sms_id)?),
+                SmsAction::Send {
+                    number,
+                    text,
+                    store,
+                } => {
+                    let sms_info = modem_info.create_sms(&number, &text)?;
+                    if store {
+                        sms_info.store("sm")?;
+                    }
+                    sms_info.send()?;
+                    println!("sucess");
+                }
+                SmsAction::Delete { sms_id } => {
+                    modem_info.delete_sms(sms_id)?;
+                    println!("sucess");
                 }
-                if let Some(name) = &filter.name {
-                    if device.device_name != *name {
-                        return false;
+                SmsAction::Store { sms_id } => {
+                    SmsInfo::new(sms_id)?.store("sm")?;
+                    println!("sucess");
+                }
+            }
+        }
+        Command::Call(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            match cmd.action {
+                CallAction::List => call::list(&modem_info.list_calls()?),
+                CallAction::Show { call_id } => call::show(&CallInfo::new(call_id)?),
+                CallAction::Dial {
+                    number,
+                    wait,
+                    timeout,
+                } => {
+                    let mut call_info = modem_info.create_call(&number)?;
+                    call_info.start()?;
+                    if wait {
+                        let waited = call_info.wait_state(
+                            &[CallState::Active],
+                            Duration::from_secs(timeout),
+                            call::state,
+                        );
+                        // don't leave unanswered calls behind in `call list`
+                        if let Err(e) = waited {
+                            if e.kind() == io::ErrorKind::TimedOut {
+                                let _ = call_info.hangup();
+                            }
+                            let _ = modem_info.delete_call(&call_info.call.dbus_path);
+                            return Err(e);
+                        }
                     }
+                    println!("sucess");
+                }
+                CallAction::Accept { call_id } => {
+                    CallInfo::new(call_id)?.accept()?;
+                    println!("sucess");
                 }
-                if let Some(state) = &filter.state {
-                    if device.connected && state == "connected" {
-                        return true;
+                CallAction::Hangup { call_id } => {
+                    match call_id {
+                        Some(call_id) => CallInfo::new(call_id)?.hangup()?,
+                        None => modem_info.hangup_all()?,
                     }
-                    if !device.connected && state == "disconnected" {
-                        return true;
+                    println!("sucess");
+                }
+                CallAction::Dtmf { call_id, tones } => {
+                    CallInfo::new(call_id)?.send_dtmf(&tones)?;
+                    println!("sucess");
+                }
+                CallAction::Delete { call_id } => {
+                    modem_info.delete_call(call_id)?;
+                    println!("sucess");
+                }
+            }
+        }
+        Command::Ussd(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            let reply = match cmd.action {
+                UssdAction::Initiate { code, parse } => {
+                    let reply = modem_info.ussd_initiate(&code)?;
+                    if parse {
+                        let balance = Balance::parse(&reply);
+                        if let Some(amount) = balance.amount {
+                            let currency = balance.currency.unwrap_or_default();
+                            println!("Balance: {} {}", amount, currency);
+                        }
+                        if let Some(data) = balance.data_mb {
+                            println!("Data:    {} MB", data);
+                        }
                     }
-                    return false;
+                    reply
+                }
+                UssdAction::Respond { response } => modem_info.ussd_respond(&response)?,
+                UssdAction::Cancel => {
+                    modem_info.ussd_cancel()?;
+                    println!("sucess");
+                    return Ok(());
+                }
+                UssdAction::Status => {
+                    let ussd = modem_info.ussd_status()?;
+                    println!("State:        {}", ussd.state());
+                    println!("Notification: {}", ussd.network_notification);
+                    println!("Request:      {}", ussd.network_request);
+                    return Ok(());
                 }
-                true
             };
-            modem::info(filter)?
+            println!("{}", reply);
+            if modem_info.ussd_status()?.state() == UssdState::UserResponse {
+                println!("(waiting for a response, use `ussd respond`)");
+            }
         }
-        Command::ListDevice(filter) => {
-            let filter = move |device: &Device| {
-                if let Some(ifindex) = filter.id {
-                    if device.ifindex != ifindex {
-                        return false;
+        Command::Location(args) => {
+            let modem_info = ModemInfo::new(modem_path(args.modem_id)?)?;
+            let mut sources = vec![LocationSource::N3gpp];
+            if args.gps {
+                sources.extend([LocationSource::GpsRaw, LocationSource::GpsNmea]);
+            }
+            if args.agps {
+                sources.push(LocationSource::AgpsMsb);
+            }
+            modem_info.location_enable(&sources)?;
+            if !args.follow {
+                location::show(&modem_info.location()?);
+                return Ok(());
+            }
+            let mut last = None;
+            loop {
+                let current = modem_info.location()?;
+                if last.as_ref() != Some(&current) {
+                    println!("{}", location::line(&current));
+                    last = Some(current);
+                }
+                thread::sleep(Duration::from_secs(args.interval));
+            }
+        }
+        Command::Signal(args) => {
+            let modem_info = ModemInfo::new(modem_path(args.modem_id)?)?;
+            let mut current = modem_info.signal()?;
+            if current.refresh_rate != args.rate {
+                modem_info.signal_setup(args.rate)?;
+                // the first measurement arrives after one refresh period
+                thread::sleep(Duration::from_secs(args.rate.into()));
+                current = modem_info.signal()?;
+            }
+            loop {
+                signal::info(&current);
+                if !args.follow {
+                    break;
+                }
+                thread::sleep(Duration::from_secs(args.rate.into()));
+                current = modem_info.signal()?;
+            }
+        }
+        Command::Modem(cmd) => {
+            let mut modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            match cmd.action {
+                ModemAction::Config {
+                    allowed,
+                    preferred,
+                    bands,
+                } => {
+                    if allowed.is_empty() && preferred.is_none() && bands.is_empty() {
+                        modem::config(&modem_info);
+                        return Ok(());
+                    }
+                    check_root()?;
+                    if !allowed.is_empty() || preferred.is_some() {
+                        let allowed = match allowed.is_empty() {
+                            true => modem_info.modem.generic.current_modes.allowed.clone(),
+                            false => allowed,
+                        };
+                        modem_info.set_modes(&ModeCombination::new(&allowed, preferred))?;
+                    }
+                    if !bands.is_empty() {
+                        modem_info.set_bands(&bands)?;
                     }
+                    println!("sucess");
                 }
-                if let Some(ifname) = &filter.name {
-                    if device.ifname != *ifname {
-                        return false;
+                ModemAction::Enable { timeout } => {
+                    check_root()?;
+                    modem_info.enable(Duration::from_secs(timeout))?;
+                    println!("sucess");
+                }
+                ModemAction::Disable { timeout } => {
+                    check_root()?;
+                    modem_info.disable(Duration::from_secs(timeout))?;
+                    println!("sucess");
+                }
+                ModemAction::Attach { timeout } => {
+                    check_root()?;
+                    modem_info.set_packet_service_state(PacketServiceState::Attached)?;
+                    modem_info.wait_attached(Duration::from_secs(timeout))?;
+                    println!("sucess");
+                }
+                ModemAction::Detach => {
+                    check_root()?;
+                    modem_info.set_packet_service_state(PacketServiceState::Detached)?;
+                    println!("sucess");
+                }
+                ModemAction::Power { state, timeout } => {
+                    let Some(state) = state else {
+                        println!("State:       {}", modem_info.modem.generic.state);
+                        println!("Power state: {}", modem_info.modem.generic.power_state);
+                        return Ok(());
+                    };
+                    check_root()?;
+                    modem_info.set_power_state(state, Duration::from_secs(timeout))?;
+                    println!("sucess");
+                }
+                ModemAction::Firmware { select, timeout } => {
+                    let modem_info = match select {
+                        Some(unique_id) => {
+                            check_root()?;
+                            modem_info.select_firmware(&unique_id, Duration::from_secs(timeout))?
+                        }
+                        None => modem_info,
+                    };
+                    modem::firmware(&modem_info, &modem_info.firmware_list()?);
+                }
+                ModemAction::Carrier { select, timeout } => {
+                    let modem_info = match select {
+                        Some(id) => {
+                            check_root()?;
+                            modem_info.select_carrier_config(&id, Duration::from_secs(timeout))?
+                        }
+                        None => modem_info,
+                    };
+                    modem::carrier_configs(&modem_info, &modem_info.carrier_configs()?);
+                }
+                ModemAction::Eps {
+                    apn,
+                    ip_type,
+                    user,
+                    password,
+                    auth,
+                } => {
+                    let unchanged = apn.is_none()
+                        && ip_type.is_none()
+                        && user.is_none()
+                        && password.is_none()
+                        && auth.is_empty();
+                    if !unchanged {
+                        check_root()?;
+                        let settings = &modem_info.modem.n3gpp.eps.initial_bearer.settings;
+                        let allowed_auth = match auth.is_empty() {
+                            true => modem_info.initial_eps_auth()?,
+                            false => auth,
+                        };
+                        modem_info.set_initial_eps_bearer(&BearerProperties {
+                            ip_type: ip_type.or(settings.ip_type.parse().ok()),
+                            user: user.unwrap_or(settings.user.clone()),
+                            password: password.unwrap_or(settings.password.clone()),
+                            allowed_auth,
+                            ..BearerProperties::new(&apn.unwrap_or(settings.apn.clone()))
+                        })?;
+                        modem_info.refresh()?;
                     }
+                    modem::eps(&modem_info, modem_info.initial_eps_bearer()?.as_ref());
                 }
-                if let Some(link_type) = &filter.link_type {
-                    if device.link_type != *link_type {
-                        return false;
+                ModemAction::Nr5g {
+                    mico_mode,
+                    drx_cycle,
+                } => {
+                    if mico_mode.is_some() || drx_cycle.is_some() {
+                        check_root()?;
+                        // ModemManager replaces both settings, keep the current one
+                        let current = &modem_info.modem.n3gpp.n5gnr.registration_settings;
+                        let keep = |value: &str| match value {
+                            "--" | "unknown" | "unsupported" => String::new(),
+                            value => value.to_string(),
+                        };
+                        modem_info.set_nr5g_registration_settings(
+                            &mico_mode.unwrap_or_else(|| keep(&current.mico_mode)),
+                            &drx_cycle.unwrap_or_else(|| keep(&current.drx_cycle)),
+                        )?;
+                        modem_info.refresh()?;
                     }
+                    modem::nr5g(&modem_info, &modem_info.signal().unwrap_or_default());
                 }
-                true
-            };
-            device::info(filter)?
+                ModemAction::Watch { interval } => {
+                    let events = modem_info.subscribe(Duration::from_secs(interval))?;
+                    for event in events {
+                        println!("{}", event);
+                    }
+                }
+                ModemAction::Reset { factory, timeout } => {
+                    check_root()?;
+                    let timeout = Duration::from_secs(timeout);
+                    let modem_info = match factory {
+                        Some(code) => modem_info.factory_reset(&code, timeout)?,
+                        None => modem_info.reset(timeout)?,
+                    };
+                    println!("Modem is now {}", modem_info.modem.dbus_path);
+                }
+            }
         }
-        Command::Lte(lte) => {
-            if lte.connect && lte.disconnect {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "Can't connect and disconnect at the same time",
-                ));
+        Command::Operator(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            match cmd.action {
+                OperatorAction::Scan { timeout } => {
+                    let operators = operator::scan(&modem_info, Duration::from_secs(timeout))?;
+                    operator::list(&operators);
+                    return Ok(());
+                }
+                OperatorAction::Register { code } => {
+                    check_root()?;
+                    modem_info.register_in_operator(&code)?;
+                }
+                OperatorAction::Auto => {
+                    check_root()?;
+                    modem_info.register_home()?;
+                }
             }
-            if utils::system::getuid() != 0 && utils::system::geteuid() != 0 {
-                return Err(io::Error::new(
-                    io::ErrorKind::PermissionDenied,
-                    "Permission denied",
-                ));
+            println!("sucess");
+        }
+        Command::Bearer(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            match cmd.action {
+                BearerAction::List => {
+                    bearer::list(&modem_info.bearers()?);
+                    return Ok(());
+                }
+                BearerAction::Show { bearer_id } => {
+                    bearer::show(&BearerInfo::new(bearer_id)?);
+                    return Ok(());
+                }
+                BearerAction::Create(args) => {
+                    check_root()?;
+                    let bearer_info = modem_info.create_bearer(&args.properties(&modem_info))?;
+                    bearer::show(&bearer_info);
+                    return Ok(());
+                }
+                BearerAction::Connect { bearer_id } => {
+                    check_root()?;
+                    BearerInfo::new(bearer_id)?.connect()?;
+                }
+                BearerAction::Disconnect { bearer_id } => {
+                    check_root()?;
+                    BearerInfo::new(bearer_id)?.disconnect()?;
+                }
+                BearerAction::Delete { bearer_id } => {
+                    check_root()?;
+                    modem_info.delete_bearer(BearerInfo::new(bearer_id)?.bearer.dbus_path)?;
+                }
+                BearerAction::Prune => {
+                    check_root()?;
+                    for bearer_info in modem_info.bearers()? {
+                        if !bearer_info.is_connected() {
+                            modem_info.delete_bearer(&bearer_info.bearer.dbus_path)?;
+                            println!("Deleted {}", bearer_info.bearer.dbus_path);
+                        }
+                    }
+                }
             }
-            let modem_id = if lte.modem_id == 0 {
-                lte.modem_id.to_string()
-            } else {
-                let modem_device_list = ModemDeviceList::new()?;
-                modem_device_list
-                    .find_by_id(lte.modem_id)
-                    .unwrap()
-                    .modem
-                    .modem
-                    .dbus_path
-                    .to_string()
+            println!("sucess");
+        }
+        Command::Profile(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            match cmd.action {
+                ProfileAction::List => {
+                    profile::list(&modem_info.profiles()?);
+                    return Ok(());
+                }
+                ProfileAction::Set {
+                    id,
+                    name,
+                    apn,
+                    apn_type,
+                    ip_type,
+                    user,
+                    password,
+                    auth,
+                } => {
+                    check_root()?;
+                    let current = match id {
+                        Some(id) => modem_info.profile(id)?,
+                        None => ModemProfile::default(),
+                    };
+                    let allowed_auth = match auth.is_empty() {
+                        true => current.allowed_auth,
+                        false => auth,
+                    };
+                    modem_info.set_profile(&ModemProfile {
+                        profile_id: id,
+                        profile_name: name.unwrap_or(current.profile_name),
+                        apn: apn.unwrap_or(current.apn),
+                        apn_type: apn_type.unwrap_or(current.apn_type),
+                        ip_type: ip_type.or(current.ip_type),
+                        user: user.unwrap_or(current.user),
+                        password: password.unwrap_or(current.password),
+                        allowed_auth,
+                    })?;
+                }
+                ProfileAction::Delete { id } => {
+                    check_root()?;
+                    modem_info.delete_profile(id)?;
+                }
+            }
+            println!("sucess");
+        }
+        Command::Time(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            let time = modem_info.network_time()?;
+            let offset = |minutes: Option<i32>| {
+                minutes
+                    .map(|m| {
+                        let sign = if m < 0 { '-' } else { '+' };
+                        format!("{}{:02}:{:02}", sign, m.abs() / 60, m.abs() % 60)
+                    })
+                    .unwrap_or_else(|| "--".to_string())
             };
-            if lte.connect {
-                let mut modem_info = ModemInfo::new(&modem_id)?;
-                if modem_info.modem.generic.state != "connected" {
-                    modem_info.connect(&lte.apn)?;
-                }
-                modem_info.refresh()?;
-                let bearer_id = modem_info.modem.generic.bearers.first().unwrap();
-                let bearer_info = BearerInfo::new(bearer_id)?;
-                let device_name = bearer_info.bearer.status.interface;
-                let mut device = Device::new(&device_name)?;
-                device.flush()?;
-                device.refresh()?;
-                let ip_v4 = format!("{}/32", bearer_info.bearer.ipv4_config.address);
-                device.add_ip(&ip_v4)?;
-                device.set_up()?;
-                device.set_ip_route()?;
-                let mut resolv=utils::resolv::Resolv::new()?;
-                for dns in bearer_info.bearer.ipv4_config.dns {
-                    resolv.add_resolv(dns);
-                }
-                resolv.update_resolv()?;
-                println!("sucess");
+            let drift = time.drift();
+            println!("Network time: {}", time.local);
+            println!("UTC offset:   {}", offset(time.utc_offset));
+            println!("DST offset:   {}", offset(time.dst_offset));
+            match drift {
+                Some(drift) => println!("Drift:        {}s", drift),
+                None => println!("Drift:        --"),
             }
-            if lte.disconnect {
-                let modem_info = ModemInfo::new(modem_id)?;
-                modem_info.disconnect()?;
-                let ports = PortList::from_vec_string(&modem_info.modem.generic.ports);
-                let device_name = ports.get_net_name();
-                let device = Device::new(&device_name)?;
-                device.del_route()?;
-                device.flush()?;
-                device.set_down()?;
-                println!("sucess");
+            if cmd.sync {
+                let (Some(unix), Some(drift)) = (time.unix, drift) else {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Network time has no UTC offset, refusing to set the clock",
+                    ));
+                };
+                if drift.unsigned_abs() > cmd.threshold {
+                    check_root()?;
+                    utils::system::set_time(unix)?;
+                    println!("Clock adjusted by {}s", -drift);
+                }
+            }
+        }
+        Command::Sim(cmd) => {
+            let modem_info = ModemInfo::new(modem_path(cmd.modem_id)?)?;
+            let sim_info = || {
+                modem_info
+                    .sim()?
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No SIM found"))
+            };
+            // show the remaining attempts before spending one
+            let pin_sim_info = || {
+                let sim_info = sim_info()?;
+                sim::retries(&modem_info);
+                Ok::<_, io::Error>(sim_info)
+            };
+            match cmd.action {
+                SimAction::Slots => sim::slots(&modem_info.sim_slots()?),
+                SimAction::Switch { slot, timeout } => {
+                    check_root()?;
+                    let slots = modem_info.sim_slots()?;
+                    if !slots.iter().any(|s| s.slot == slot) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("Modem has no sim slot {}", slot),
+                        ));
+                    }
+                    let modem_info =
+                        modem_info.set_primary_sim_slot(slot, Duration::from_secs(timeout))?;
+                    println!("Modem is now {}", modem_info.modem.dbus_path);
+                    sim::slots(&modem_info.sim_slots()?);
+                }
+                SimAction::Info => sim::show(&modem_info, &sim_info()?),
+                SimAction::Pin { pin } => {
+                    pin_sim_info()?.send_pin(&pin)?;
+                    println!("sucess");
+                }
+                SimAction::Puk { puk, new_pin } => {
+                    pin_sim_info()?.send_puk(&puk, &new_pin)?;
+                    println!("sucess");
+                }
+                SimAction::ChangePin { old_pin, new_pin } => {
+                    pin_sim_info()?.change_pin(&old_pin, &new_pin)?;
+                    println!("sucess");
+                }
+                SimAction::EnablePin { pin } => {
+                    pin_sim_info()?.enable_pin(&pin)?;
+                    println!("sucess");
+                }
+                SimAction::DisablePin { pin } => {
+                    pin_sim_info()?.disable_pin(&pin)?;
+                    println!("sucess");
+                }
             }
         }
     }
     Ok(())
 }
+
+fn parse_query<F: Fields>(query: &Option<String>) -> io::Result<Option<Query>> {
+    match query {
+        Some(query) => {
+            let query = Query::parse(query)?;
+            query.validate::<F>()?;
+            Ok(Some(query))
+        }
+        None => Ok(None),
+    }
+}
+
+fn check_root() -> io::Result<()> {
+    if !utils::system::is_root() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Permission denied",
+        ));
+    }
+    Ok(())
+}
+
+/// Resolve the modem id given on the command line to something mmcli accepts.
+fn modem_path(modem_id: u32) -> io::Result<String> {
+    if modem_id == 0 {
+        return Ok(modem_id.to_string());
+    }
+    let modem_device_list = ModemDeviceList::new()?;
+    modem_device_list
+        .find_by_id(modem_id)
+        .map(|device| device.modem.modem.dbus_path.to_string())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Modem {} not found", modem_id),
+            )
+        })
+}
@@ -0,0 +1,48 @@
+use prettytable::{row, Table};
+use struch::modemmanager::call::CallInfo;
+
+use crate::format::object_id;
+
+pub fn list(call_list: &[CallInfo]) {
+    let mut tab = Table::new();
+    tab.add_row(row![
+        Frb->"Call",
+        Fgb->"Number",
+        Fgb->"Direction",
+        Fyb->"State",
+        Fyb->"Reason",
+    ]);
+    for info in call_list {
+        let call = &info.call;
+        tab.add_row(row![
+            Fr->object_id(&call.dbus_path),
+            Fg->&call.properties.number,
+            Fg->&call.properties.direction,
+            Fy->&call.properties.state,
+            Fy->&call.properties.state_reason,
+        ]);
+    }
+    tab.printstd();
+}
+
+pub fn show(info: &CallInfo) {
+    let call = &info.call;
+    println!("Call:       {}", object_id(&call.dbus_path));
+    println!("Number:     {}", call.properties.number);
+    println!("Direction:  {}", call.properties.direction);
+    println!("State:      {}", call.properties.state);
+    println!("Reason:     {}", call.properties.state_reason);
+    println!("Multiparty: {}", call.properties.multiparty);
+    println!("Audio port: {}", call.properties.audio_port);
+}
+
+/// One line per state change while waiting on a call.
+pub fn state(info: &CallInfo) {
+    let call = &info.call;
+    println!(
+        "[{}] {} ({})",
+        object_id(&call.dbus_path),
+        call.properties.state,
+        call.properties.state_reason
+    );
+}
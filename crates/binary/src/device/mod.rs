@@ -1,32 +1,87 @@
 use std::io;
 
-use prettytable::{row, Table};
+use clap::ValueEnum;
+use prettytable::{row, Cell, Row, Table};
 use struch::iproute2::{Device, DeviceList};
 
-pub fn info<F>(f: F) -> io::Result<()>
+/// Optional hardware columns of `list-device`.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Column {
+    Driver,
+    Bus,
+    Usb,
+    UsbPath,
+    Pci,
+    Speed,
+    Duplex,
+    Carrier,
+    Kind,
+}
+
+impl Column {
+    fn title(&self) -> &'static str {
+        match self {
+            Column::Driver => "Driver",
+            Column::Bus => "Bus",
+            Column::Usb => "USB ID",
+            Column::UsbPath => "USB Path",
+            Column::Pci => "PCI Slot",
+            Column::Speed => "Speed",
+            Column::Duplex => "Duplex",
+            Column::Carrier => "Carrier",
+            Column::Kind => "Kind",
+        }
+    }
+
+    fn value(&self, device: &Device) -> String {
+        let hardware = &device.hardware;
+        let text = |value: &Option<String>| value.clone().unwrap_or_else(|| "--".to_string());
+        match self {
+            Column::Driver => text(&hardware.driver),
+            Column::Bus => text(&hardware.bus),
+            Column::Usb => text(&hardware.usb_id),
+            Column::UsbPath => text(&hardware.usb_path),
+            Column::Pci => text(&hardware.pci_slot),
+            Column::Speed => text(&hardware.speed.map(|s| format!("{}Mb/s", s))),
+            Column::Duplex => text(&hardware.duplex),
+            Column::Carrier => text(&hardware.carrier.map(|c| c.to_string())),
+            Column::Kind => hardware.kind.to_string(),
+        }
+    }
+}
+
+pub fn info<F>(f: F, columns: &[Column]) -> io::Result<()>
 where
     F: Fn(&Device) -> bool + 'static,
 {
     let device_list = DeviceList::new()?;
     let mut tab = Table::new();
-    tab.add_row(row![
+    let mut title = row![
         Frb->"Device ID",
         Fgb->"Name",
         Fgb->"Type",
         Fgb->"MAC",
         Fgb->"State",
         Frb->"IP"
-    ]);
+    ];
+    for column in columns {
+        title.add_cell(Cell::new(column.title()).style_spec("Fcb"));
+    }
+    tab.add_row(title);
     macro_rules! color {
         ($style:ident,$device:expr) => {
-            tab.add_row(row![
+            let mut row: Row = row![
                     Fr->$device.ifindex,
                     Fb->$device.ifname,
                     Fg->$device.link_type,
                     Fg->$device.address.as_ref().unwrap_or(&String::new()),
                     $style->$device.operstate,
                     Fg->&$device.get_ip_addr()
-                ]);
+                ];
+            for column in columns {
+                row.add_cell(Cell::new(&column.value($device)).style_spec("Fc"));
+            }
+            tab.add_row(row);
         };
     }
     for device in device_list.get_address_list().iter() {
@@ -45,14 +100,3 @@ where
     tab.printstd();
     Ok(())
 }
-
-#[macro_export]
-macro_rules! filter {
-    ($key:ident,$value:expr) => {
-        |device: &Device| device.$key == $value
-    };
-}
-
-pub fn default_filter(_device: &Device) -> bool {
-    true
-}
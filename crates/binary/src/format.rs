@@ -0,0 +1,15 @@
+//! Small text helpers shared by the display modules.
+
+/// ModemManager prints `--` for unset values, do the same for empty ones.
+pub fn or_dash(value: &str) -> &str {
+    match value {
+        "" => "--",
+        value => value,
+    }
+}
+
+/// The trailing index of a ModemManager object path, as accepted by
+/// `mmcli -b`, `-s` or `-o`.
+pub fn object_id(dbus_path: &str) -> &str {
+    dbus_path.rsplit('/').next().unwrap_or(dbus_path)
+}
@@ -1,2 +1,12 @@
+pub mod bearer;
+pub mod call;
 pub mod device;
+pub mod format;
+pub mod location;
 pub mod modem;
+pub mod operator;
+pub mod profile;
+pub mod query;
+pub mod signal;
+pub mod sim;
+pub mod sms;
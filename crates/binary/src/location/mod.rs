@@ -0,0 +1,44 @@
+use std::fmt::Display;
+
+use struch::modemmanager::location::Location;
+
+fn text<T: Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "--".to_string())
+}
+
+fn hex(value: &Option<u32>) -> String {
+    value
+        .map(|v| format!("{:X}", v))
+        .unwrap_or_else(|| "--".to_string())
+}
+
+pub fn show(location: &Location) {
+    println!("MCC:       {}", text(&location.mcc));
+    println!("MNC:       {}", text(&location.mnc));
+    println!("LAC:       {}", hex(&location.lac));
+    println!("TAC:       {}", hex(&location.tac));
+    println!("CID:       {}", hex(&location.cid));
+    println!("Latitude:  {}", text(&location.latitude));
+    println!("Longitude: {}", text(&location.longitude));
+    println!("Altitude:  {}", text(&location.altitude));
+    println!("Fix time:  {}", text(&location.fix_time));
+}
+
+/// One line per update for follow mode.
+pub fn line(location: &Location) -> String {
+    format!(
+        "mcc={} mnc={} lac={} tac={} cid={} lat={} lon={} alt={} utc={}",
+        text(&location.mcc),
+        text(&location.mnc),
+        hex(&location.lac),
+        hex(&location.tac),
+        hex(&location.cid),
+        text(&location.latitude),
+        text(&location.longitude),
+        text(&location.altitude),
+        text(&location.fix_time),
+    )
+}
@@ -0,0 +1,20 @@
+use struch::modemmanager::modem::ModemInfo;
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn config(modem_info: &ModemInfo) {
+    let generic = &modem_info.modem.generic;
+    println!("Current modes:   {}", generic.current_modes);
+    println!("Supported modes:");
+    for modes in generic.supported_modes.iter() {
+        println!("  {}", modes);
+    }
+    println!("Current bands:   {}", join(&generic.current_bands));
+    println!("Supported bands: {}", join(&generic.supported_bands));
+}
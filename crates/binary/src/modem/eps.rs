@@ -0,0 +1,46 @@
+use struch::modemmanager::{bearer::BearerInfo, modem::ModemInfo};
+
+use crate::format::or_dash;
+
+/// Configured initial EPS bearer settings next to the bearer the modem
+/// attached with.
+pub fn eps(modem_info: &ModemInfo, effective: Option<&BearerInfo>) {
+    let n3gpp = &modem_info.modem.n3gpp;
+    let settings = &n3gpp.eps.initial_bearer.settings;
+    println!("Registration:      {}", n3gpp.registration_state);
+    println!(
+        "UE mode:           {}",
+        or_dash(&n3gpp.eps.ue_mode_operation)
+    );
+    println!("                   configured           effective");
+    let (apn, ip_type, user, connected) = match effective {
+        Some(bearer_info) => {
+            let bearer = &bearer_info.bearer;
+            (
+                or_dash(&bearer.properties.apn),
+                or_dash(&bearer.properties.ip_type),
+                or_dash(&bearer.properties.user),
+                or_dash(&bearer.status.connected),
+            )
+        }
+        None => ("--", "--", "--", "--"),
+    };
+    println!("APN:               {:<20} {}", or_dash(&settings.apn), apn);
+    println!(
+        "IP type:           {:<20} {}",
+        or_dash(&settings.ip_type),
+        ip_type
+    );
+    println!(
+        "User:              {:<20} {}",
+        or_dash(&settings.user),
+        user
+    );
+    println!("Attached:                               {}", connected);
+    if let Some(bearer_info) = effective {
+        let error = &bearer_info.bearer.status.connection_error;
+        if !error.name.is_empty() {
+            println!("Error:             {} ({})", error.message, error.name);
+        }
+    }
+}
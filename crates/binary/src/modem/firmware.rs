@@ -0,0 +1,39 @@
+use prettytable::{row, Table};
+use struch::modemmanager::{
+    firmware::{CarrierConfig, Firmware},
+    modem::ModemInfo,
+};
+
+pub fn firmware(modem_info: &ModemInfo, images: &[Firmware]) {
+    let generic = &modem_info.modem.generic;
+    println!("Revision:          {}", generic.revision);
+    println!("Hardware revision: {}", generic.hardware_revision);
+    let mut tab = Table::new();
+    tab.add_row(row![Frb->"Unique Id", Fgb->"Type", Fyb->"Current"]);
+    for image in images {
+        if image.current {
+            tab.add_row(row![Fr->image.unique_id, Fg->image.image_type, Fg->"yes"]);
+            continue;
+        }
+        tab.add_row(row![Fr->image.unique_id, Fg->image.image_type, Fr->"no"]);
+    }
+    tab.printstd();
+}
+
+pub fn carrier_configs(modem_info: &ModemInfo, configs: &[CarrierConfig]) {
+    let generic = &modem_info.modem.generic;
+    println!(
+        "Carrier configuration: {} ({})",
+        generic.carrier_configuration, generic.carrier_configuration_revision
+    );
+    let mut tab = Table::new();
+    tab.add_row(row![Frb->"Id", Fgb->"Description", Fgb->"Version", Fyb->"Active"]);
+    for config in configs {
+        if config.active {
+            tab.add_row(row![Fr->config.id, Fg->config.description, Fg->config.version, Fg->"yes"]);
+            continue;
+        }
+        tab.add_row(row![Fr->config.id, Fg->config.description, Fg->config.version, Fr->"no"]);
+    }
+    tab.printstd();
+}
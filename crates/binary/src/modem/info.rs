@@ -3,6 +3,7 @@ use std::io;
 use prettytable::row;
 use prettytable::Table;
 use struch::modemmanager::device::{ModemDevice, ModemDeviceList};
+use struch::modemmanager::signal::{MetricKind, Technology};
 
 pub fn info<F>(f: F) -> io::Result<()>
 where
@@ -16,9 +17,22 @@ where
         Fgb->"Name",
         Fyb->"NET IP",
         Fbb->"SIM IP",
+        Fgb->"Registration",
+        Fgb->"Packet",
+        Fgb->"5G",
+        Fgb->"NR RSRP",
         Fgb->"Connect",
     ]);
     for device in device_list.get_modem_device_list().iter() {
+        let registration = &device.modem.modem.n3gpp.registration_state;
+        let packet = device.modem.packet_service_state();
+        let nr5g_mode = device.modem.nr5g_mode(Some(&device.signal));
+        let nr_rsrp = device
+            .signal
+            .get(Technology::Nr5g)
+            .and_then(|nr| nr.metric(MetricKind::Rsrp))
+            .map(|rsrp| format!("{:.1} dBm", rsrp))
+            .unwrap_or_else(|| "--".to_string());
         if f(device) {
             if device.connected {
                 tab.add_row(row![
@@ -27,6 +41,10 @@ where
                     Fg->&device.device_name,
                     Fg->&device.net_ip,
                     Fb->&device.sim_ip,
+                    Fg->registration,
+                    Fg->packet,
+                    Fg->nr5g_mode,
+                    Fg->nr_rsrp,
                     Fg->&device.connected,
                 ]);
                 continue;
@@ -37,6 +55,10 @@ where
                 Fg->&device.device_name,
                 Fg->&device.net_ip,
                 Fb->&device.sim_ip,
+                Fg->registration,
+                Fg->packet,
+                Fg->nr5g_mode,
+                Fg->nr_rsrp,
                 Fr->device.connected,
             ]);
         };
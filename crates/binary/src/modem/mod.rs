@@ -1,2 +1,10 @@
+mod config;
+mod eps;
+mod firmware;
 mod info;
+mod nr5g;
+pub use config::config;
+pub use eps::eps;
+pub use firmware::{carrier_configs, firmware};
 pub use info::info;
+pub use nr5g::nr5g;
@@ -0,0 +1,26 @@
+use struch::modemmanager::{
+    modem::ModemInfo,
+    signal::{Signal, Technology},
+};
+
+pub fn nr5g(modem_info: &ModemInfo, signal: &Signal) {
+    let settings = &modem_info.modem.n3gpp.n5gnr.registration_settings;
+    println!(
+        "Access tech: {}",
+        modem_info.modem.generic.access_technologies.join(", ")
+    );
+    println!("5G mode:     {}", modem_info.nr5g_mode(Some(signal)));
+    println!("MICO mode:   {}", settings.mico_mode);
+    println!("DRX cycle:   {}", settings.drx_cycle);
+    if let Some(nr) = signal.get(Technology::Nr5g) {
+        for metric in nr.metrics.iter() {
+            println!(
+                "NR {:<8} {:.1} {} ({})",
+                format!("{}:", metric.kind),
+                metric.value,
+                metric.unit(),
+                metric.grade(Technology::Nr5g)
+            );
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    io::{self, Write},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use prettytable::{row, Table};
+use struch::modemmanager::{
+    modem::ModemInfo,
+    operator::{Availability, NetworkOperator},
+};
+
+/// Run the network scan in the background and print the elapsed time on
+/// stderr, a scan blocks for several minutes.
+pub fn scan(modem_info: &ModemInfo, timeout: Duration) -> io::Result<Vec<NetworkOperator>> {
+    let (tx, rx) = mpsc::channel();
+    let modem_info = modem_info.clone();
+    thread::spawn(move || {
+        let _ = tx.send(modem_info.scan_networks(timeout));
+    });
+    let start = Instant::now();
+    let spinner = ['|', '/', '-', '\\'];
+    let mut tick = 0;
+    loop {
+        match rx.recv_timeout(Duration::from_millis(250)) {
+            Ok(result) => {
+                eprintln!(
+                    "\rScanning networks... done in {}s",
+                    start.elapsed().as_secs()
+                );
+                return result;
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                eprint!(
+                    "\rScanning networks... {} {}s",
+                    spinner[tick % spinner.len()],
+                    start.elapsed().as_secs()
+                );
+                let _ = io::stderr().flush();
+                tick += 1;
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                eprintln!();
+                return Err(io::Error::other("Network scan aborted"));
+            }
+        }
+    }
+}
+
+pub fn list(operators: &[NetworkOperator]) {
+    let mut tab = Table::new();
+    tab.add_row(row![
+        Frb->"Code",
+        Fgb->"Name",
+        Fgb->"Access Tech",
+        Fyb->"Availability",
+    ]);
+    for operator in operators {
+        let technologies = operator.access_technologies.join(", ");
+        match operator.availability {
+            Availability::Current | Availability::Available => tab.add_row(row![
+                Fr->operator.code,
+                Fg->operator.name,
+                Fg->technologies,
+                Fg->operator.availability,
+            ]),
+            _ => tab.add_row(row![
+                Fr->operator.code,
+                Fg->operator.name,
+                Fg->technologies,
+                Fr->operator.availability,
+            ]),
+        };
+    }
+    tab.printstd();
+}
@@ -0,0 +1,43 @@
+use prettytable::{row, Table};
+use struch::modemmanager::profile::Profile;
+
+use crate::format::or_dash;
+
+pub fn list(profiles: &[Profile]) {
+    let mut tab = Table::new();
+    tab.add_row(row![
+        Frb->"Id",
+        Fgb->"Name",
+        Fgb->"APN",
+        Fgb->"APN Type",
+        Fgb->"IP Type",
+        Fgb->"User",
+        Fyb->"Auth",
+    ]);
+    for profile in profiles {
+        let id = profile
+            .profile_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "--".to_string());
+        let ip_type = profile
+            .ip_type
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "--".to_string());
+        let auth = profile
+            .allowed_auth
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+        tab.add_row(row![
+            Fr->id,
+            Fg->or_dash(&profile.profile_name),
+            Fg->or_dash(&profile.apn),
+            Fg->or_dash(&profile.apn_type),
+            Fg->ip_type,
+            Fg->or_dash(&profile.user),
+            Fy->or_dash(&auth),
+        ]);
+    }
+    tab.printstd();
+}
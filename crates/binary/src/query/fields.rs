@@ -0,0 +1,153 @@
+use struch::{
+    iproute2::Device,
+    modemmanager::{
+        device::ModemDevice,
+        signal::{MetricKind, Technology},
+    },
+};
+
+use super::Value;
+
+/// Something a [`super::Query`] can be evaluated against.
+pub trait Fields {
+    /// Field names accepted in queries, used for validation and help.
+    fn names() -> &'static [&'static str];
+
+    fn field(&self, name: &str) -> Option<Value>;
+}
+
+impl Fields for Device {
+    fn names() -> &'static [&'static str] {
+        &[
+            "id",
+            "name",
+            "type",
+            "mac",
+            "operstate",
+            "mtu",
+            "flags",
+            "family",
+            "ip",
+            "has-ipv4",
+            "has-ipv6",
+            "driver",
+            "bus",
+            "usb-id",
+            "usb-path",
+            "pci-slot",
+            "speed",
+            "duplex",
+            "carrier",
+            "kind",
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<Value> {
+        let families = self
+            .addr_info
+            .iter()
+            .map(|addr| addr.family.clone())
+            .collect::<Vec<String>>();
+        let value = match name {
+            "id" => Value::Num(self.ifindex as f64),
+            "name" => self.ifname.as_str().into(),
+            "type" => self.link_type.as_str().into(),
+            "mac" => self.address.clone().unwrap_or_default().into(),
+            "operstate" => self.operstate.as_str().into(),
+            "mtu" => Value::Num(self.mtu as f64),
+            "flags" => self.flags.clone().into(),
+            "family" => families.into(),
+            "ip" => self
+                .addr_info
+                .iter()
+                .map(|addr| addr.local.clone())
+                .collect::<Vec<String>>()
+                .into(),
+            "has-ipv4" => families.iter().any(|f| f == "inet").into(),
+            "has-ipv6" => families.iter().any(|f| f == "inet6").into(),
+            "driver" => self.hardware.driver.clone().unwrap_or_default().into(),
+            "bus" => self.hardware.bus.clone().unwrap_or_default().into(),
+            "usb-id" => self.hardware.usb_id.clone().unwrap_or_default().into(),
+            "usb-path" => self.hardware.usb_path.clone().unwrap_or_default().into(),
+            "pci-slot" => self.hardware.pci_slot.clone().unwrap_or_default().into(),
+            "speed" => Value::Num(self.hardware.speed.unwrap_or_default() as f64),
+            "duplex" => self.hardware.duplex.clone().unwrap_or_default().into(),
+            "carrier" => self.hardware.carrier.unwrap_or_default().into(),
+            "kind" => self.hardware.kind.to_string().into(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+impl Fields for ModemDevice {
+    fn names() -> &'static [&'static str] {
+        &[
+            "id",
+            "name",
+            "hardware",
+            "state",
+            "connected",
+            "net-ip",
+            "sim-ip",
+            "has-ipv4",
+            "signal",
+            "operator",
+            "operator-code",
+            "registration",
+            "packet-service",
+            "nr5g-mode",
+            "nr-rsrp",
+            "mico-mode",
+            "drx-cycle",
+            "access-tech",
+            "manufacturer",
+            "model",
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<Value> {
+        let modem = &self.modem.modem;
+        let value = match name {
+            "id" => Value::Num(self.device_id as f64),
+            "name" => self.device_name.as_str().into(),
+            "hardware" => self.hardware_name.as_str().into(),
+            "state" => modem.generic.state.as_str().into(),
+            "connected" => self.connected.into(),
+            "net-ip" => self.net_ip.as_str().into(),
+            "sim-ip" => self.sim_ip.as_str().into(),
+            "has-ipv4" => (!self.sim_ip.is_empty() && self.sim_ip != "--").into(),
+            "signal" => modem.generic.signal_quality.value.as_str().into(),
+            "operator" => modem.n3gpp.operator_name.as_str().into(),
+            "operator-code" => modem.n3gpp.operator_code.as_str().into(),
+            "registration" => modem.n3gpp.registration_state.as_str().into(),
+            "packet-service" => self.modem.packet_service_state().to_string().into(),
+            "nr5g-mode" => self.modem.nr5g_mode(Some(&self.signal)).to_string().into(),
+            "nr-rsrp" => self
+                .signal
+                .get(Technology::Nr5g)
+                .and_then(|nr| nr.metric(MetricKind::Rsrp))
+                .map(Value::Num)
+                .unwrap_or(Value::Str(String::new())),
+            "mico-mode" => modem
+                .n3gpp
+                .n5gnr
+                .registration_settings
+                .mico_mode
+                .as_str()
+                .into(),
+            "drx-cycle" => modem
+                .n3gpp
+                .n5gnr
+                .registration_settings
+                .drx_cycle
+                .as_str()
+                .into(),
+            "access-tech" => modem.generic.access_technologies.clone().into(),
+            "manufacturer" => modem.generic.manufacturer.as_str().into(),
+            "model" => modem.generic.model.as_str().into(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
@@ -0,0 +1,459 @@
+//! A small filter language shared by `list-device` and `list-modem`.
+//!
+//! ```text
+//! operstate=UP and name~^wwan and has-ipv4
+//! not (signal<30 or access-tech=gsm)
+//! ```
+//!
+//! A condition is `field op value` with op one of `= != ~ !~ > >= < <=`,
+//! or a bare field which is true when the field is set. Conditions combine
+//! with `and`, `or`, `not` and parentheses. List fields such as `flags`
+//! match when any of their elements matches.
+mod fields;
+
+use std::{fmt::Display, io};
+
+use regex::Regex;
+
+pub use fields::Fields;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    List(Vec<String>),
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<Vec<String>> for Value {
+    fn from(value: Vec<String>) -> Self {
+        Value::List(value)
+    }
+}
+
+impl Value {
+    fn is_set(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            Value::Num(n) => *n != 0.0,
+            Value::Str(s) => !s.is_empty() && s != "--",
+            Value::List(l) => !l.is_empty(),
+        }
+    }
+
+    fn strings(&self) -> Vec<String> {
+        match self {
+            Value::Bool(b) => vec![b.to_string()],
+            Value::Num(n) => vec![n.to_string()],
+            Value::Str(s) => vec![s.clone()],
+            Value::List(l) => l.clone(),
+        }
+    }
+
+    fn num(&self) -> Option<f64> {
+        match self {
+            Value::Num(n) => Some(*n),
+            Value::Str(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Match,
+    NotMatch,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    fn parse(s: &str) -> Option<Op> {
+        match s {
+            "=" | "==" => Some(Op::Eq),
+            "!=" => Some(Op::Ne),
+            "~" => Some(Op::Match),
+            "!~" => Some(Op::NotMatch),
+            ">" => Some(Op::Gt),
+            ">=" => Some(Op::Ge),
+            "<" => Some(Op::Lt),
+            "<=" => Some(Op::Le),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Rhs {
+    Text(String),
+    /// Case sensitive text, used by `Query::equals`
+    Exact(String),
+    Regex(Regex),
+}
+
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Set(String),
+    Cmp(String, Op, Rhs),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    Op(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError(String);
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid query: {}", self.0)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl From<QueryError> for io::Error {
+    fn from(e: QueryError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, QueryError> {
+    let chars = s.chars().collect::<Vec<char>>();
+    let is_op = |c: char| matches!(c, '=' | '!' | '~' | '<' | '>');
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' {
+            tokens.push(Token::Open);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::Close);
+            i += 1;
+        } else if is_op(c) {
+            let start = i;
+            while i < chars.len() && is_op(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Op(chars[start..i].iter().collect()));
+            // the value after an operator is taken verbatim so that regexes
+            // may contain parentheses and operator characters
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            let value: String = match chars.get(i) {
+                Some(&quote) if quote == '"' || quote == '\'' => {
+                    let start = i + 1;
+                    i = start;
+                    while i < chars.len() && chars[i] != quote {
+                        i += 1;
+                    }
+                    if i == chars.len() {
+                        return Err(QueryError("unterminated quote".to_string()));
+                    }
+                    i += 1;
+                    chars[start..i - 1].iter().collect()
+                }
+                _ => {
+                    let start = i;
+                    while i < chars.len() && !chars[i].is_whitespace() {
+                        i += 1;
+                    }
+                    // leave closing parentheses of the expression alone
+                    while i > start && chars[i - 1] == ')' && !balanced(&chars[start..i]) {
+                        i -= 1;
+                    }
+                    if i == start {
+                        continue;
+                    }
+                    chars[start..i].iter().collect()
+                }
+            };
+            tokens.push(Token::Word(value));
+        } else {
+            let start = i;
+            while i < chars.len()
+                && !chars[i].is_whitespace()
+                && !is_op(chars[i])
+                && chars[i] != '('
+                && chars[i] != ')'
+            {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        }
+    }
+    Ok(tokens)
+}
+
+fn balanced(chars: &[char]) -> bool {
+    let open = chars.iter().filter(|c| **c == '(').count();
+    let close = chars.iter().filter(|c| **c == ')').count();
+    open >= close
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn or(&mut self) -> Result<Query, QueryError> {
+        let mut lhs = self.and()?;
+        while self.keyword("or") {
+            self.pos += 1;
+            lhs = Query::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Query, QueryError> {
+        let mut lhs = self.not()?;
+        while self.keyword("and") {
+            self.pos += 1;
+            lhs = Query::And(Box::new(lhs), Box::new(self.not()?));
+        }
+        Ok(lhs)
+    }
+
+    fn not(&mut self) -> Result<Query, QueryError> {
+        if self.keyword("not") {
+            self.pos += 1;
+            return Ok(Query::Not(Box::new(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Query, QueryError> {
+        match self.next() {
+            Some(Token::Open) => {
+                let query = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(query),
+                    _ => Err(QueryError("expected ')'".to_string())),
+                }
+            }
+            Some(Token::Word(field)) => {
+                let op = match self.peek() {
+                    Some(Token::Op(op)) => op.clone(),
+                    _ => return Ok(Query::Set(field.to_lowercase())),
+                };
+                self.pos += 1;
+                let op = Op::parse(&op)
+                    .ok_or_else(|| QueryError(format!("unknown operator '{}'", op)))?;
+                let value = match self.next() {
+                    Some(Token::Word(value)) => value,
+                    _ => return Err(QueryError(format!("missing value after '{}'", field))),
+                };
+                let rhs = match op {
+                    Op::Match | Op::NotMatch => {
+                        Rhs::Regex(Regex::new(&value).map_err(|e| QueryError(e.to_string()))?)
+                    }
+                    _ => Rhs::Text(value),
+                };
+                Ok(Query::Cmp(field.to_lowercase(), op, rhs))
+            }
+            Some(token) => Err(QueryError(format!("unexpected {:?}", token))),
+            None => Err(QueryError("unexpected end of query".to_string())),
+        }
+    }
+}
+
+impl Query {
+    pub fn parse(s: &str) -> Result<Query, QueryError> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+        let query = parser.or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(token) => Err(QueryError(format!("unexpected {:?}", token))),
+        }
+    }
+
+    /// `field=value`, except that text is compared case sensitively.
+    pub fn equals(field: &str, value: impl Display) -> Query {
+        Query::Cmp(field.to_string(), Op::Eq, Rhs::Exact(value.to_string()))
+    }
+
+    /// Join the given queries with `and`, `None` when there are none.
+    pub fn all(queries: impl IntoIterator<Item = Option<Query>>) -> Option<Query> {
+        queries
+            .into_iter()
+            .flatten()
+            .reduce(|lhs, rhs| Query::And(Box::new(lhs), Box::new(rhs)))
+    }
+
+    /// Check that every field used by the query is known to `F`.
+    pub fn validate<F: Fields>(&self) -> Result<(), QueryError> {
+        match self {
+            Query::And(lhs, rhs) | Query::Or(lhs, rhs) => {
+                lhs.validate::<F>()?;
+                rhs.validate::<F>()
+            }
+            Query::Not(query) => query.validate::<F>(),
+            Query::Set(field) | Query::Cmp(field, _, _) => {
+                if F::names().contains(&field.as_str()) {
+                    Ok(())
+                } else {
+                    Err(QueryError(format!(
+                        "unknown field '{}', expected one of: {}",
+                        field,
+                        F::names().join(", ")
+                    )))
+                }
+            }
+        }
+    }
+
+    pub fn matches<F: Fields>(&self, item: &F) -> bool {
+        match self {
+            Query::And(lhs, rhs) => lhs.matches(item) && rhs.matches(item),
+            Query::Or(lhs, rhs) => lhs.matches(item) || rhs.matches(item),
+            Query::Not(query) => !query.matches(item),
+            Query::Set(field) => item.field(field).map(|v| v.is_set()).unwrap_or(false),
+            Query::Cmp(field, op, rhs) => match item.field(field) {
+                Some(value) => compare(&value, *op, rhs),
+                None => false,
+            },
+        }
+    }
+}
+
+fn compare(value: &Value, op: Op, rhs: &Rhs) -> bool {
+    match (op, rhs) {
+        (Op::Match, Rhs::Regex(re)) => value.strings().iter().any(|s| re.is_match(s)),
+        (Op::NotMatch, Rhs::Regex(re)) => !value.strings().iter().any(|s| re.is_match(s)),
+        (Op::Eq, Rhs::Text(text)) => equals(value, text, false),
+        (Op::Ne, Rhs::Text(text)) => !equals(value, text, false),
+        (Op::Eq, Rhs::Exact(text)) => equals(value, text, true),
+        (_, Rhs::Text(text)) => {
+            let (Some(lhs), Ok(rhs)) = (value.num(), text.parse::<f64>()) else {
+                return false;
+            };
+            match op {
+                Op::Gt => lhs > rhs,
+                Op::Ge => lhs >= rhs,
+                Op::Lt => lhs < rhs,
+                Op::Le => lhs <= rhs,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+fn equals(value: &Value, text: &str, exact: bool) -> bool {
+    match value {
+        Value::Num(n) => text.parse::<f64>().map(|t| t == *n).unwrap_or(false),
+        Value::Bool(b) => text.parse::<bool>().map(|t| t == *b).unwrap_or(false),
+        _ if exact => value.strings().iter().any(|s| s == text),
+        _ => value.strings().iter().any(|s| s.eq_ignore_ascii_case(text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item;
+
+    impl Fields for Item {
+        fn names() -> &'static [&'static str] {
+            &["name", "operstate", "flags", "signal", "has-ipv4"]
+        }
+
+        fn field(&self, name: &str) -> Option<Value> {
+            match name {
+                "name" => Some("wwan0".into()),
+                "operstate" => Some("UP".into()),
+                "flags" => Some(vec!["UP".to_string(), "NOARP".to_string()].into()),
+                "signal" => Some(Value::Num(42.0)),
+                "has-ipv4" => Some(true.into()),
+                _ => None,
+            }
+        }
+    }
+
+    fn check(query: &str) -> bool {
+        let query = Query::parse(query).unwrap();
+        query.validate::<Item>().unwrap();
+        query.matches(&Item)
+    }
+
+    #[test]
+    fn test_query() {
+        assert!(check("operstate=UP and name~^wwan and has-ipv4"));
+        assert!(check("flags=noarp"));
+        assert!(check("signal>=40 and signal<50"));
+        assert!(check("not signal<30"));
+        assert!(check(
+            "(name=eth0 or name~'^ww(an)?') and not flags=LOWER_UP"
+        ));
+        assert!(!check("operstate != UP or name !~ wwan"));
+        let query = Query::all([
+            Some(Query::equals("name", "wwan0")),
+            None,
+            Some(Query::equals("signal", 42)),
+        ]);
+        assert!(query.unwrap().matches(&Item));
+        assert!(!Query::equals("name", "WWAN0").matches(&Item));
+        assert!(check("name=WWAN0"));
+        assert!(Query::all([None]).is_none());
+    }
+
+    #[test]
+    fn test_query_error() {
+        assert!(Query::parse("name=").is_err());
+        assert!(Query::parse("(name=wwan0").is_err());
+        assert!(Query::parse("name~(").is_err());
+        assert!(Query::parse("bogus=1").unwrap().validate::<Item>().is_err());
+    }
+}
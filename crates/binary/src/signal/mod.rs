@@ -0,0 +1,33 @@
+use prettytable::{Cell, Row, Table};
+use struch::modemmanager::signal::{Grade, Signal};
+
+fn style(grade: Grade) -> &'static str {
+    match grade {
+        Grade::Excellent => "Fg",
+        Grade::Good => "Fc",
+        Grade::Fair => "Fy",
+        Grade::Poor => "Fr",
+    }
+}
+
+pub fn info(signal: &Signal) {
+    let mut tab = Table::new();
+    tab.add_row(Row::new(vec![
+        Cell::new("Technology").style_spec("Frb"),
+        Cell::new("Metric").style_spec("Fgb"),
+        Cell::new("Value").style_spec("Fyb"),
+        Cell::new("Grade").style_spec("Fbb"),
+    ]));
+    for tech in signal.technologies.iter() {
+        for metric in tech.metrics.iter() {
+            let grade = metric.grade(tech.technology);
+            tab.add_row(Row::new(vec![
+                Cell::new(&tech.technology.to_string()).style_spec("Fr"),
+                Cell::new(&metric.kind.to_string()).style_spec("Fg"),
+                Cell::new(&format!("{:.1} {}", metric.value, metric.unit())).style_spec("Fy"),
+                Cell::new(&grade.to_string()).style_spec(style(grade)),
+            ]));
+        }
+    }
+    tab.printstd();
+}
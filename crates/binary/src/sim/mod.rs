@@ -0,0 +1,62 @@
+use prettytable::{row, Table};
+use struch::modemmanager::{
+    modem::ModemInfo,
+    sim::{SimInfo, SimSlot},
+};
+
+pub fn show(modem_info: &ModemInfo, sim_info: &SimInfo) {
+    let sim = &sim_info.sim.properties;
+    println!("SIM:               {}", sim_info.sim.dbus_path);
+    println!("Active:            {}", sim.active);
+    println!("Type:              {}", sim.sim_type);
+    println!("ICCID:             {}", sim.iccid);
+    println!("IMSI:              {}", sim.imsi);
+    println!("EID:               {}", sim.eid);
+    println!(
+        "Operator:          {} ({})",
+        sim.operator_name, sim.operator_code
+    );
+    println!("Emergency numbers: {}", sim.emergency_numbers.join(", "));
+    retries(modem_info);
+}
+
+pub fn retries(modem_info: &ModemInfo) {
+    println!(
+        "Unlock required:   {}",
+        modem_info.modem.generic.unlock_required
+    );
+    for retries in modem_info.unlock_retries() {
+        println!(
+            "{:<18} {} retries left",
+            format!("{}:", retries.lock),
+            retries.retries
+        );
+    }
+}
+
+pub fn slots(slots: &[SimSlot]) {
+    let mut tab = Table::new();
+    tab.add_row(row![
+        Frb->"Slot",
+        Fgb->"ICCID",
+        Fgb->"IMSI",
+        Fgb->"Operator",
+        Fyb->"Primary",
+    ]);
+    for slot in slots {
+        let (iccid, imsi, operator) = match &slot.sim {
+            Some(sim) => (
+                sim.sim.properties.iccid.as_str(),
+                sim.sim.properties.imsi.as_str(),
+                sim.sim.properties.operator_name.as_str(),
+            ),
+            None => ("--", "--", "--"),
+        };
+        if slot.primary {
+            tab.add_row(row![Fr->slot.slot, Fg->iccid, Fg->imsi, Fg->operator, Fg->slot.primary]);
+            continue;
+        }
+        tab.add_row(row![Fr->slot.slot, Fg->iccid, Fg->imsi, Fg->operator, Fr->slot.primary]);
+    }
+    tab.printstd();
+}
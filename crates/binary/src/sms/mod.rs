@@ -0,0 +1,43 @@
+use std::io;
+
+use prettytable::{row, Table};
+use struch::modemmanager::sms::SmsInfo;
+
+use crate::format::object_id;
+
+pub fn list(sms_list: &[SmsInfo]) -> io::Result<()> {
+    let mut tab = Table::new();
+    tab.add_row(row![
+        Frb->"SMS",
+        Fgb->"Number",
+        Fgb->"State",
+        Fgb->"Storage",
+        Fyb->"Timestamp",
+        Fbb->"Text",
+    ]);
+    for info in sms_list {
+        let sms = &info.sms;
+        tab.add_row(row![
+            Fr->object_id(&sms.dbus_path),
+            Fg->&sms.content.number,
+            Fg->&sms.properties.state,
+            Fg->&sms.properties.storage,
+            Fy->&sms.properties.timestamp,
+            Fb->&sms.content.text,
+        ]);
+    }
+    tab.printstd();
+    Ok(())
+}
+
+pub fn show(info: &SmsInfo) {
+    let sms = &info.sms;
+    println!("SMS:       {}", object_id(&sms.dbus_path));
+    println!("Number:    {}", sms.content.number);
+    println!("State:     {}", sms.properties.state);
+    println!("Storage:   {}", sms.properties.storage);
+    println!("Timestamp: {}", sms.properties.timestamp);
+    println!("SMSC:      {}", sms.properties.smsc);
+    println!();
+    println!("{}", sms.content.text);
+}
@@ -0,0 +1,197 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use utils::resolv::Resolv;
+
+use super::{DhcpClient, Ppp};
+use crate::{
+    iproute2::Device,
+    modemmanager::{
+        bearer::{BearerInfo, IpMethod},
+        modem::ModemInfo,
+    },
+    nftables::Share,
+    sysfs::Qmi,
+};
+
+static STATE_DIR: &str = "/run/netool";
+
+/// The interface traffic of a connected bearer leaves through: the ppp
+/// interface for the `ppp` ip method, the bearer interface otherwise.
+pub fn uplink(bearer_info: &BearerInfo) -> Option<String> {
+    let interface = &bearer_info.bearer.status.interface;
+    match bearer_info.bearer.ipv4_config.method() {
+        IpMethod::Ppp => Ppp::new(interface).interface(),
+        _ if interface.is_empty() || interface == "--" => None,
+        _ => Some(interface.clone()),
+    }
+}
+
+/// Configure the host side of a connected bearer according to the ip method
+/// the modem reports, returning the uplink interface.
+///
+/// IPv6-only bearers get IPv6 connectivity only: 464XLAT needs a CLAT
+/// daemon such as clatd, which is not started here.
+pub fn bring_up(modem_info: &ModemInfo, bearer_info: &BearerInfo) -> io::Result<String> {
+    let bearer = &bearer_info.bearer;
+    let ipv4_config = &bearer.ipv4_config;
+    let ipv6_config = &bearer.ipv6_config;
+    let mut dns = bearer_info.dns();
+    let ppp = ipv4_config.method() == IpMethod::Ppp;
+    let device_name = match ppp {
+        true => {
+            let ppp = Ppp::new(&bearer.status.interface);
+            let ifname = ppp.start(Duration::from_secs(30))?;
+            dns.extend(ppp.peer_dns());
+            ifname
+        }
+        false => bearer.status.interface.clone(),
+    };
+    let mut device = Device::new(&device_name)?;
+    if !ppp {
+        device.flush()?;
+        if let Some(qmi) = Qmi::detect(&device_name, &modem_info.modem.generic.drivers) {
+            qmi.ensure_raw_ip()?;
+        }
+        device.refresh()?;
+        if let Some(mtu) = ipv4_config.mtu().or(ipv6_config.mtu()) {
+            device.set_mtu(mtu)?;
+        }
+    }
+    let static_ipv4 = !matches!(ipv4_config.method(), IpMethod::Dhcp | IpMethod::Ppp);
+    if let Some(ip_v4) = ipv4_config.cidr().filter(|_| static_ipv4) {
+        device.add_ip(&ip_v4)?;
+    }
+    // with the dhcp method the kernel configures IPv6 from router advertisements
+    let static_ipv6 = ipv6_config.method() != IpMethod::Dhcp;
+    if bearer_info.has_ipv6() {
+        device.enable_ipv6()?;
+        if let Some(ip_v6) = ipv6_config.cidr().filter(|_| static_ipv6) {
+            device.add_ip(&ip_v6)?;
+        }
+    }
+    device.set_up()?;
+    match ipv4_config.method() {
+        IpMethod::Dhcp => {
+            let client = DhcpClient::detect().ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "No DHCP client found, install udhcpc, dhclient or dhcpcd",
+                )
+            })?;
+            client.request(&device_name)?;
+            device.fix_route_metric()?;
+        }
+        IpMethod::Ppp => device.set_ip_route()?,
+        _ if bearer_info.has_ipv4() => match ipv4_config.gateway() {
+            Some(gateway) => device.set_ip_route_via(gateway)?,
+            None => device.set_ip_route()?,
+        },
+        _ => {}
+    }
+    if bearer_info.has_ipv6() && static_ipv6 {
+        device.set_ip6_route(ipv6_config.gateway())?;
+    }
+    let mut resolv = Resolv::new()?;
+    for dns in dns {
+        resolv.add_resolv(dns);
+    }
+    record_dns(&device_name, resolv.add_nameserver.iter())?;
+    resolv.update_resolv()?;
+    Ok(device_name)
+}
+
+/// Disconnect the modem and undo `bring_up`, including any share of the
+/// uplink. Only the name servers `bring_up` added leave resolv.conf.
+pub fn tear_down(modem_info: &ModemInfo) -> io::Result<()> {
+    // the ip config is gone once the bearers are disconnected
+    let bearers = modem_info
+        .bearers()?
+        .into_iter()
+        .filter(|b| b.is_connected())
+        .collect::<Vec<BearerInfo>>();
+    let mut dns = Vec::new();
+    let method = |method: IpMethod| {
+        bearers
+            .iter()
+            .any(|b| b.bearer.ipv4_config.method() == method)
+    };
+    let mut device_name = modem_info.net_device_name();
+    let ppp_bearers = bearers
+        .iter()
+        .filter(|b| b.bearer.ipv4_config.method() == IpMethod::Ppp);
+    for bearer_info in ppp_bearers {
+        let ppp = Ppp::new(&bearer_info.bearer.status.interface);
+        if let Some(ifname) = ppp.interface() {
+            dns.extend(take_recorded_dns(&ifname)?);
+            if Share::is_active(&ifname) {
+                Share::stop(&ifname)?;
+            }
+            ppp.stop()?;
+            // the ppp interface disappears with pppd
+            device_name.clear();
+        }
+    }
+    modem_info.disconnect()?;
+    if !device_name.is_empty() {
+        dns.extend(take_recorded_dns(&device_name)?);
+        if Share::is_active(&device_name) {
+            Share::stop(&device_name)?;
+        }
+        if method(IpMethod::Dhcp) {
+            if let Some(client) = DhcpClient::detect() {
+                client.release(&device_name)?;
+            }
+        }
+        let device = Device::new(&device_name)?;
+        if bearers.iter().any(|b| b.has_ipv4()) && !method(IpMethod::Dhcp) {
+            device.del_route()?;
+        }
+        let static_ipv6 = |b: &BearerInfo| b.bearer.ipv6_config.method() != IpMethod::Dhcp;
+        if bearers.iter().any(|b| b.has_ipv6() && static_ipv6(b)) {
+            device.del_ip6_route()?;
+        }
+        device.flush()?;
+        device.set_down()?;
+    }
+    let mut resolv = Resolv::new()?;
+    for dns in dns {
+        resolv.del_resolv(dns);
+    }
+    resolv.update_resolv()
+}
+
+fn dns_state_file(ifname: &str) -> String {
+    format!("{}/dns-{}", STATE_DIR, ifname)
+}
+
+/// Remember the name servers `bring_up` added to resolv.conf for `ifname`,
+/// so `tear_down` leaves the ones that were there before alone.
+fn record_dns<'a>(ifname: &str, added: impl Iterator<Item = &'a String>) -> io::Result<()> {
+    let state = dns_state_file(ifname);
+    // a repeated bring_up finds its own servers already present
+    let mut servers = fs::read_to_string(&state)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>();
+    for server in added {
+        if !servers.contains(server) {
+            servers.push(server.clone());
+        }
+    }
+    fs::create_dir_all(STATE_DIR)?;
+    fs::write(&state, servers.join("\n"))
+}
+
+fn take_recorded_dns(ifname: &str) -> io::Result<Vec<String>> {
+    let state = dns_state_file(ifname);
+    if !Path::new(&state).exists() {
+        return Ok(Vec::new());
+    }
+    let servers = fs::read_to_string(&state)?
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    fs::remove_file(&state)?;
+    Ok(servers)
+}
@@ -0,0 +1,63 @@
+use std::io;
+
+use cmd_lib::run_cmd;
+
+use super::which;
+
+/// A DHCP client found on the system, used for bearers with the `dhcp`
+/// ip method.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DhcpClient {
+    Udhcpc,
+    Dhclient,
+    Dhcpcd,
+}
+
+impl DhcpClient {
+    /// The first installed client, busybox udhcpc is preferred since it is
+    /// what most routers ship.
+    pub fn detect() -> Option<Self> {
+        [DhcpClient::Udhcpc, DhcpClient::Dhclient, DhcpClient::Dhcpcd]
+            .into_iter()
+            .find(|client| which(client.program()).is_some())
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self {
+            DhcpClient::Udhcpc => "udhcpc",
+            DhcpClient::Dhclient => "dhclient",
+            DhcpClient::Dhcpcd => "dhcpcd",
+        }
+    }
+
+    /// Obtain a lease on `ifname` and return once it is configured.
+    pub fn request(&self, ifname: &str) -> io::Result<()> {
+        match self {
+            DhcpClient::Udhcpc => run_cmd!(
+                udhcpc -i $ifname -n -q -t 5
+            ),
+            DhcpClient::Dhclient => run_cmd!(
+                dhclient -1 $ifname
+            ),
+            DhcpClient::Dhcpcd => run_cmd!(
+                dhcpcd -w $ifname
+            ),
+        }
+        .map_err(io::Error::other)
+    }
+
+    /// Release the lease, udhcpc exits after configuring so there is
+    /// nothing to release.
+    pub fn release(&self, ifname: &str) -> io::Result<()> {
+        match self {
+            DhcpClient::Udhcpc => Ok(()),
+            DhcpClient::Dhclient => run_cmd!(
+                dhclient -r $ifname
+            ),
+            DhcpClient::Dhcpcd => run_cmd!(
+                dhcpcd -k $ifname
+            ),
+        }
+        .map_err(io::Error::other)
+    }
+}
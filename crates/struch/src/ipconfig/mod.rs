@@ -0,0 +1,18 @@
+//! Host side configuration of connected bearers, including what is not
+//! done with iproute2 directly: DHCP clients and pppd.
+mod bearer;
+mod dhcp;
+mod ppp;
+pub use bearer::{bring_up, tear_down, uplink};
+pub use dhcp::DhcpClient;
+pub use ppp::Ppp;
+
+use std::{env, path::PathBuf};
+
+/// Look up `program` in `PATH`.
+fn which(program: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|file| file.is_file())
+}
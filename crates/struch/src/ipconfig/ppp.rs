@@ -0,0 +1,97 @@
+use std::{fs, io, thread, time::Duration};
+
+use cmd_lib::run_cmd;
+
+static RUN_DIR: &str = "/run";
+static PEER_DNS: &str = "/etc/ppp/resolv.conf";
+
+/// pppd session on a modem tty, used for bearers with the `ppp` ip method.
+///
+/// ModemManager has already dialed on the port, pppd only negotiates the
+/// link. The session is named after the port so it can be found again to
+/// stop it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ppp {
+    /// Data port reported as the bearer interface, e.g. `ttyUSB2`
+    pub port: String,
+}
+
+impl Ppp {
+    pub fn new(port: &str) -> Self {
+        Ppp {
+            port: port.to_string(),
+        }
+    }
+
+    fn link_name(&self) -> String {
+        format!("netool_{}", self.port)
+    }
+
+    /// pppd writes its pid and the interface name to this file.
+    fn pid_file(&self) -> String {
+        format!("{}/ppp-{}.pid", RUN_DIR, self.link_name())
+    }
+
+    /// Start pppd and wait up to `timeout` for the link, returning the ppp
+    /// interface name.
+    pub fn start(&self, timeout: Duration) -> io::Result<String> {
+        let device = format!("/dev/{}", self.port);
+        let link_name = self.link_name();
+        run_cmd!(
+            pppd $device 460800 noauth nodefaultroute noipdefault usepeerdns
+                linkname $link_name
+        )
+        .map_err(io::Error::other)?;
+        let step = Duration::from_millis(500);
+        let mut waited = Duration::ZERO;
+        while waited < timeout {
+            if let Some(ifname) = self.interface() {
+                return Ok(ifname);
+            }
+            thread::sleep(step);
+            waited += step;
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("No ppp link on {}", self.port),
+        ))
+    }
+
+    /// The ppp interface of a running session.
+    pub fn interface(&self) -> Option<String> {
+        let content = fs::read_to_string(self.pid_file()).ok()?;
+        content
+            .lines()
+            .nth(1)
+            .map(|ifname| ifname.trim().to_string())
+            .filter(|ifname| !ifname.is_empty())
+    }
+
+    /// Name servers negotiated by pppd with `usepeerdns`.
+    pub fn peer_dns(&self) -> Vec<String> {
+        fs::read_to_string(PEER_DNS)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.strip_prefix("nameserver"))
+            .map(|ns| ns.trim().to_string())
+            .collect()
+    }
+
+    pub fn is_active(&self) -> bool {
+        fs::metadata(self.pid_file()).is_ok()
+    }
+
+    pub fn stop(&self) -> io::Result<()> {
+        let content = fs::read_to_string(self.pid_file())?;
+        let pid = content
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        run_cmd!(
+            kill $pid
+        )
+        .map_err(io::Error::other)
+    }
+}
@@ -1,8 +1,10 @@
-use std::{fmt::Display, io, str::FromStr};
+use std::{fmt::Display, fs, io, str::FromStr};
 
 use cmd_lib::run_fun;
 use serde_derive::{Deserialize, Serialize};
 
+use crate::sysfs::Hardware;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DeviceList(Vec<Device>);
@@ -24,21 +26,30 @@ impl FromStr for DeviceList {
 impl DeviceList {
     #[allow(dead_code)]
     pub fn new() -> io::Result<Self> {
-        run_fun!(
+        let device_list: Self = run_fun!(
             ip -j address show
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+        .map_err(io::Error::other)?
         .parse()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)?;
+        Ok(device_list.with_hardware())
     }
 
     pub fn from_device(device_name: &str) -> io::Result<Self> {
-        run_fun!(
+        let device_list: Self = run_fun!(
             ip -j address show dev $device_name
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+        .map_err(io::Error::other)?
         .parse()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)?;
+        Ok(device_list.with_hardware())
+    }
+
+    fn with_hardware(mut self) -> Self {
+        for device in self.0.iter_mut() {
+            device.hardware = Hardware::new(&device.ifname);
+        }
+        self
     }
 
     pub fn get_address_list(&self) -> &Vec<Device> {
@@ -56,7 +67,7 @@ impl DeviceList {
     pub fn fist(&self) -> io::Result<&Device> {
         self.0
             .first()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "No device found"))
+            .ok_or_else(|| io::Error::other("No device found"))
     }
 }
 
@@ -77,6 +88,8 @@ pub struct Device {
     pub broadcast: Option<String>,
     #[serde(rename = "addr_info")]
     pub addr_info: Vec<AddrInfo>,
+    #[serde(skip)]
+    pub hardware: Hardware,
 }
 
 impl FromStr for Device {
@@ -99,7 +112,7 @@ impl Device {
         run_fun!(
             ip addr add $ip_addr dev $ifname
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
         .map(|_| ())
     }
 
@@ -108,7 +121,7 @@ impl Device {
         run_fun!(
             ip route del default dev $ifname metric 200
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
         .map(|_| ())
     }
 
@@ -117,7 +130,7 @@ impl Device {
         run_fun!(
             ip link set $ifname up
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
         .map(|_| ())
     }
 
@@ -126,7 +139,7 @@ impl Device {
         run_fun!(
             ip link set $ifname down
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
         .map(|_| ())
     }
 
@@ -135,7 +148,7 @@ impl Device {
         run_fun!(
             ip addr flush dev $ifname
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
         .map(|_| ())
     }
 
@@ -159,9 +172,95 @@ impl Device {
         run_fun!(
             ip route add default dev $ifname metric 200
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
         .map(|_| ())
     }
+
+    /// Add the default route via `gateway`, which may be outside the prefix
+    /// of point to point wwan links.
+    pub fn set_ip_route_via(&self, gateway: &str) -> io::Result<()> {
+        let ifname = self.ifname.clone();
+        run_fun!(
+            ip route add default via $gateway dev $ifname onlink metric 200
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Move the IPv4 default route a DHCP client installed to metric 200,
+    /// where the static routes go.
+    pub fn fix_route_metric(&self) -> io::Result<()> {
+        let ifname = self.ifname.clone();
+        let routes = run_fun!(
+            ip route show default dev $ifname
+        )
+        .map_err(io::Error::other)?;
+        // e.g. `default via 10.0.0.1 proto dhcp src 10.0.0.2 metric 1024`
+        let mut gateways = Vec::new();
+        for route in routes.lines() {
+            let words = route.split_whitespace().collect::<Vec<&str>>();
+            let value = |key: &str| {
+                let i = words.iter().position(|w| *w == key)?;
+                words.get(i + 1).map(|v| v.to_string())
+            };
+            let metric = value("metric").unwrap_or_else(|| "0".to_string());
+            if metric == "200" {
+                return Ok(());
+            }
+            run_fun!(
+                ip route del default dev $ifname metric $metric
+            )
+            .map_err(io::Error::other)?;
+            gateways.push(value("via"));
+        }
+        match gateways.into_iter().next() {
+            Some(Some(gateway)) => self.set_ip_route_via(&gateway),
+            Some(None) => self.set_ip_route(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn set_mtu(&self, mtu: u32) -> io::Result<()> {
+        let ifname = self.ifname.clone();
+        run_fun!(
+            ip link set $ifname mtu $mtu
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Add the IPv6 default route, via `gateway` when the bearer reports one.
+    pub fn set_ip6_route(&self, gateway: Option<&str>) -> io::Result<()> {
+        let ifname = self.ifname.clone();
+        match gateway {
+            Some(gateway) => run_fun!(
+                ip -6 route add default via $gateway dev $ifname metric 200
+            ),
+            None => run_fun!(
+                ip -6 route add default dev $ifname metric 200
+            ),
+        }
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn del_ip6_route(&self) -> io::Result<()> {
+        let ifname = self.ifname.clone();
+        run_fun!(
+            ip -6 route del default dev $ifname metric 200
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Wwan interfaces often come up with IPv6 disabled.
+    ///
+    /// The proc file is written directly, `sysctl` would read the dots of
+    /// VLAN names such as `wwan0.1` as key separators.
+    pub fn enable_ipv6(&self) -> io::Result<()> {
+        let path = format!("/proc/sys/net/ipv6/conf/{}/disable_ipv6", self.ifname);
+        fs::write(path, "0")
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1,2 +1,6 @@
+pub mod ipconfig;
 pub mod iproute2;
 pub mod modemmanager;
+pub mod nftables;
+pub mod provider;
+pub mod sysfs;
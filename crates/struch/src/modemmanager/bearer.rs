@@ -4,6 +4,8 @@ use cmd_lib::run_fun;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::{mmcli, modem::ModemInfo};
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BearerInfo {
@@ -29,9 +31,9 @@ impl BearerInfo {
         run_fun!(
             mmcli -b $bearer_id -J
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+        .map_err(io::Error::other)?
         .parse()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
     }
 
     pub fn refresh(&mut self) -> io::Result<()> {
@@ -39,6 +41,115 @@ impl BearerInfo {
         self.bearer = Self::new(dbus_path)?.bearer;
         Ok(())
     }
+
+    pub fn is_connected(&self) -> bool {
+        self.bearer.status.connected == "yes"
+    }
+
+    pub fn connect(&self) -> io::Result<()> {
+        let dbus_path = self.bearer.dbus_path.clone();
+        run_fun!(
+            mmcli -b $dbus_path --connect
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn has_ipv4(&self) -> bool {
+        known(&self.bearer.ipv4_config.address).is_some()
+    }
+
+    pub fn has_ipv6(&self) -> bool {
+        known(&self.bearer.ipv6_config.address).is_some()
+    }
+
+    /// IPv4 and IPv6 name servers of the bearer.
+    pub fn dns(&self) -> Vec<String> {
+        let ipv4 = self.bearer.ipv4_config.dns.iter();
+        let ipv6 = self.bearer.ipv6_config.dns.iter();
+        ipv4.chain(ipv6)
+            .filter_map(|dns| known(dns).map(|dns| dns.to_string()))
+            .collect()
+    }
+
+    pub fn disconnect(&self) -> io::Result<()> {
+        let dbus_path = self.bearer.dbus_path.clone();
+        run_fun!(
+            mmcli -b $dbus_path --disconnect
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+fn known(value: &str) -> Option<&str> {
+    match value {
+        "" | "--" => None,
+        value => Some(value),
+    }
+}
+
+/// How the modem expects the host to configure the interface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IpMethod {
+    /// Address, prefix and gateway are given in the bearer
+    Static,
+    /// Run a DHCP client on the net port
+    Dhcp,
+    /// Run pppd on the tty reported as the bearer interface
+    Ppp,
+    Unknown,
+}
+
+impl From<&str> for IpMethod {
+    fn from(s: &str) -> Self {
+        match s {
+            "static" => IpMethod::Static,
+            "dhcp" => IpMethod::Dhcp,
+            "ppp" => IpMethod::Ppp,
+            _ => IpMethod::Unknown,
+        }
+    }
+}
+
+impl Ipv4Config {
+    pub fn method(&self) -> IpMethod {
+        IpMethod::from(self.method.as_str())
+    }
+
+    /// The address with the bearer prefix, `/32` when none is reported.
+    pub fn cidr(&self) -> Option<String> {
+        let prefix = known(&self.prefix).unwrap_or("32");
+        known(&self.address).map(|address| format!("{}/{}", address, prefix))
+    }
+
+    pub fn gateway(&self) -> Option<&str> {
+        known(&self.gateway)
+    }
+
+    pub fn mtu(&self) -> Option<u32> {
+        self.mtu.parse().ok()
+    }
+}
+
+impl Ipv6Config {
+    pub fn method(&self) -> IpMethod {
+        IpMethod::from(self.method.as_str())
+    }
+
+    /// The address with the bearer prefix, `/64` when none is reported.
+    pub fn cidr(&self) -> Option<String> {
+        let prefix = known(&self.prefix).unwrap_or("64");
+        known(&self.address).map(|address| format!("{}/{}", address, prefix))
+    }
+
+    pub fn gateway(&self) -> Option<&str> {
+        known(&self.gateway)
+    }
+
+    pub fn mtu(&self) -> Option<u32> {
+        self.mtu.parse().ok()
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -72,7 +183,7 @@ pub struct Ipv4Config {
 #[serde(rename_all = "camelCase")]
 pub struct Ipv6Config {
     pub address: String,
-    pub dns: Vec<Value>,
+    pub dns: Vec<String>,
     pub gateway: String,
     pub method: String,
     pub mtu: String,
@@ -148,3 +259,227 @@ pub struct ConnectionError {
     pub message: String,
     pub name: String,
 }
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum IpType {
+    Ipv4,
+    Ipv6,
+    #[default]
+    Ipv4v6,
+}
+
+impl FromStr for IpType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ipv4" => Ok(IpType::Ipv4),
+            "ipv6" => Ok(IpType::Ipv6),
+            "ipv4v6" => Ok(IpType::Ipv4v6),
+            _ => Err(format!("Invalid ip type: {}", s)),
+        }
+    }
+}
+
+impl Display for IpType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IpType::Ipv4 => write!(f, "ipv4"),
+            IpType::Ipv6 => write!(f, "ipv6"),
+            IpType::Ipv4v6 => write!(f, "ipv4v6"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Auth {
+    None,
+    Pap,
+    Chap,
+    Mschap,
+    Mschapv2,
+    Eap,
+}
+
+impl FromStr for Auth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Auth::None),
+            "pap" => Ok(Auth::Pap),
+            "chap" => Ok(Auth::Chap),
+            "mschap" => Ok(Auth::Mschap),
+            "mschapv2" => Ok(Auth::Mschapv2),
+            "eap" => Ok(Auth::Eap),
+            _ => Err(format!("Invalid auth method: {}", s)),
+        }
+    }
+}
+
+impl Display for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Auth::None => write!(f, "none"),
+            Auth::Pap => write!(f, "pap"),
+            Auth::Chap => write!(f, "chap"),
+            Auth::Mschap => write!(f, "mschap"),
+            Auth::Mschapv2 => write!(f, "mschapv2"),
+            Auth::Eap => write!(f, "eap"),
+        }
+    }
+}
+
+/// Properties of a bearer to create or connect, unset values are left to
+/// the modem defaults.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct BearerProperties {
+    pub apn: String,
+    pub ip_type: Option<IpType>,
+    pub user: String,
+    pub password: String,
+    pub allowed_auth: Vec<Auth>,
+    pub allow_roaming: Option<bool>,
+    /// `home`, `partner` and `non-partner` joined by `|`
+    pub roaming_allowance: String,
+    /// e.g. `default`, `ims`, `mms`, `internet`
+    pub apn_type: String,
+    pub profile_id: Option<u32>,
+    /// `none`, `requested` or `required`
+    pub multiplex: String,
+}
+
+impl BearerProperties {
+    pub fn new(apn: &str) -> Self {
+        BearerProperties {
+            apn: apn.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The `key=value` list accepted by `--create-bearer` and `--simple-connect`.
+    pub fn to_args(&self) -> io::Result<String> {
+        let option = |value: Option<String>| value.unwrap_or_default();
+        let allowed_auth = self
+            .allowed_auth
+            .iter()
+            .map(|auth| auth.to_string())
+            .collect::<Vec<String>>()
+            .join("|");
+        let allow_roaming = self
+            .allow_roaming
+            .map(|allow| if allow { "yes" } else { "no" }.to_string());
+        mmcli::key_values(&[
+            ("apn", self.apn.clone()),
+            ("ip-type", option(self.ip_type.map(|t| t.to_string()))),
+            ("user", self.user.clone()),
+            ("password", self.password.clone()),
+            ("allowed-auth", allowed_auth),
+            ("allow-roaming", option(allow_roaming)),
+            ("roaming-allowance", self.roaming_allowance.clone()),
+            ("apn-type", self.apn_type.clone()),
+            (
+                "profile-id",
+                option(self.profile_id.map(|id| id.to_string())),
+            ),
+            ("multiplex", self.multiplex.clone()),
+        ])
+    }
+}
+
+impl ModemInfo {
+    /// All bearers of the modem, connected or not.
+    pub fn bearers(&self) -> io::Result<Vec<BearerInfo>> {
+        self.modem
+            .generic
+            .bearers
+            .iter()
+            .map(BearerInfo::new)
+            .collect()
+    }
+
+    /// The first connected bearer, if any.
+    pub fn connected_bearer(&self) -> io::Result<Option<BearerInfo>> {
+        Ok(self.bearers()?.into_iter().find(|b| b.is_connected()))
+    }
+
+    pub fn create_bearer(&self, properties: &BearerProperties) -> io::Result<BearerInfo> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = properties.to_args()?;
+        let reply = run_fun!(
+            mmcli -m $dbus_path --create-bearer=$args
+        )
+        .map_err(io::Error::other)?;
+        BearerInfo::new(mmcli::created_path(&reply)?)
+    }
+
+    pub fn delete_bearer(&self, bearer_id: impl Display) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let bearer_id = bearer_id.to_string();
+        run_fun!(
+            mmcli -m $dbus_path --delete-bearer=$bearer_id
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Connect with `--simple-connect`, which reuses or creates a bearer
+    /// matching the properties.
+    pub fn connect_with(&self, properties: &BearerProperties) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = properties.to_args()?;
+        run_fun!(
+            mmcli -m $dbus_path --simple-connect=$args
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_config() {
+        let json = r#"{"address":"2408:8456:3e10::1","dns":["2408:8899::8","--"],"gateway":"2408:8456:3e10::2","method":"static","mtu":"1500","prefix":"64"}"#;
+        let mut bearer_info = BearerInfo::default();
+        bearer_info.bearer.ipv4_config.address = "--".to_string();
+        bearer_info.bearer.ipv6_config = serde_json::from_str(json).unwrap();
+        assert!(!bearer_info.has_ipv4());
+        assert!(bearer_info.has_ipv6());
+        assert_eq!(
+            bearer_info.bearer.ipv6_config.cidr().as_deref(),
+            Some("2408:8456:3e10::1/64")
+        );
+        assert_eq!(bearer_info.dns(), vec!["2408:8899::8"]);
+        assert_eq!(bearer_info.bearer.ipv6_config.method(), IpMethod::Static);
+        assert_eq!(bearer_info.bearer.ipv6_config.mtu(), Some(1500));
+        let ipv4_config = Ipv4Config {
+            address: "10.64.12.7".to_string(),
+            prefix: "30".to_string(),
+            method: "dhcp".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(ipv4_config.cidr().as_deref(), Some("10.64.12.7/30"));
+        assert_eq!(ipv4_config.method(), IpMethod::Dhcp);
+    }
+
+    #[test]
+    fn test_bearer_properties() {
+        let properties = BearerProperties {
+            ip_type: Some(IpType::Ipv4),
+            user: "user".to_string(),
+            password: "secret".to_string(),
+            allowed_auth: vec![Auth::Pap, Auth::Chap],
+            allow_roaming: Some(false),
+            profile_id: Some(2),
+            ..BearerProperties::new("private.apn")
+        };
+        assert_eq!(
+            properties.to_args().unwrap(),
+            "apn='private.apn',ip-type='ipv4',user='user',password='secret',\
+             allowed-auth='pap|chap',allow-roaming='no',profile-id='2'"
+        );
+    }
+}
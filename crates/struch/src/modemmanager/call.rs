@@ -0,0 +1,285 @@
+use std::{fmt::Display, io, str::FromStr, time::Duration};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    mmcli::{created_path, key_values},
+    modem::{poll_until, ModemInfo},
+};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallList {
+    #[serde(rename = "modem.voice.call", default)]
+    pub call_list: Vec<String>,
+}
+
+impl FromStr for CallList {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallInfo {
+    pub call: Call,
+}
+
+impl FromStr for CallInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+impl Display for CallInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).unwrap())
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Call {
+    #[serde(rename = "dbus-path")]
+    pub dbus_path: String,
+    pub properties: CallProperties,
+}
+
+/// Audio fields are only present for modems with a separate audio port.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CallProperties {
+    #[serde(rename = "audio-port")]
+    pub audio_port: String,
+    pub direction: String,
+    pub multiparty: String,
+    pub number: String,
+    pub state: String,
+    #[serde(rename = "state-reason")]
+    pub state_reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallState {
+    Unknown,
+    Dialing,
+    RingingOut,
+    RingingIn,
+    Active,
+    Held,
+    Waiting,
+    Terminated,
+}
+
+impl FromStr for CallState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dialing" => Ok(CallState::Dialing),
+            "ringing-out" => Ok(CallState::RingingOut),
+            "ringing-in" => Ok(CallState::RingingIn),
+            "active" => Ok(CallState::Active),
+            "held" => Ok(CallState::Held),
+            "waiting" => Ok(CallState::Waiting),
+            "terminated" => Ok(CallState::Terminated),
+            "unknown" => Ok(CallState::Unknown),
+            _ => Err(format!("Invalid call state: {}", s)),
+        }
+    }
+}
+
+impl Display for CallState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallState::Unknown => write!(f, "unknown"),
+            CallState::Dialing => write!(f, "dialing"),
+            CallState::RingingOut => write!(f, "ringing-out"),
+            CallState::RingingIn => write!(f, "ringing-in"),
+            CallState::Active => write!(f, "active"),
+            CallState::Held => write!(f, "held"),
+            CallState::Waiting => write!(f, "waiting"),
+            CallState::Terminated => write!(f, "terminated"),
+        }
+    }
+}
+
+impl CallInfo {
+    pub fn new(call_id: impl Display) -> io::Result<CallInfo> {
+        run_fun!(
+            mmcli -o $call_id -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)
+    }
+
+    pub fn refresh(&mut self) -> io::Result<()> {
+        let dbus_path = self.call.dbus_path.clone();
+        self.call = Self::new(dbus_path)?.call;
+        Ok(())
+    }
+
+    pub fn state(&self) -> CallState {
+        self.call
+            .properties
+            .state
+            .parse()
+            .unwrap_or(CallState::Unknown)
+    }
+
+    /// Dial an outgoing call created with `ModemInfo::create_call`.
+    pub fn start(&self) -> io::Result<()> {
+        let dbus_path = self.call.dbus_path.clone();
+        run_fun!(
+            mmcli -o $dbus_path --start
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn accept(&self) -> io::Result<()> {
+        let dbus_path = self.call.dbus_path.clone();
+        run_fun!(
+            mmcli -o $dbus_path --accept
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn hangup(&self) -> io::Result<()> {
+        let dbus_path = self.call.dbus_path.clone();
+        run_fun!(
+            mmcli -o $dbus_path --hangup
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Send DTMF tones on an active call, `tones` is made of `0-9`, `A-D`,
+    /// `*` and `#`.
+    pub fn send_dtmf(&self, tones: &str) -> io::Result<()> {
+        let valid = |c: char| c.is_ascii_digit() || matches!(c, 'A'..='D' | '*' | '#');
+        if tones.is_empty() || !tones.chars().all(valid) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid DTMF tones: {}", tones),
+            ));
+        }
+        let dbus_path = self.call.dbus_path.clone();
+        for tone in tones.chars() {
+            let tone = tone.to_string();
+            run_fun!(
+                mmcli -o $dbus_path --send-dtmf=$tone
+            )
+            .map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    /// Poll the call every second until it reaches one of `states`,
+    /// `on_change` is called with every new state seen. A call that ends
+    /// first fails with `ConnectionRefused`, a timeout with `TimedOut`.
+    pub fn wait_state(
+        &mut self,
+        states: &[CallState],
+        timeout: Duration,
+        mut on_change: impl FnMut(&CallInfo),
+    ) -> io::Result<CallState> {
+        let mut last = None;
+        let reached = poll_until(timeout, || {
+            self.refresh()?;
+            let state = self.state();
+            if last != Some(state) {
+                on_change(self);
+                last = Some(state);
+            }
+            if states.contains(&state) {
+                return Ok(Some(state));
+            }
+            if state == CallState::Terminated {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionRefused,
+                    format!("Call is {} ({})", state, self.call.properties.state_reason),
+                ));
+            }
+            Ok(None)
+        })?;
+        reached.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "Call is {} ({})",
+                    self.state(),
+                    self.call.properties.state_reason
+                ),
+            )
+        })
+    }
+}
+
+impl ModemInfo {
+    pub fn list_calls(&self) -> io::Result<Vec<CallInfo>> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let call_list: CallList = run_fun!(
+            mmcli -m $dbus_path --voice-list-calls -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        call_list.call_list.iter().map(CallInfo::new).collect()
+    }
+
+    /// Create an outgoing call, it is dialed with `CallInfo::start`.
+    pub fn create_call(&self, number: &str) -> io::Result<CallInfo> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = key_values(&[("number", number.to_string())])?;
+        let reply = run_fun!(
+            mmcli -m $dbus_path --voice-create-call=$args
+        )
+        .map_err(io::Error::other)?;
+        CallInfo::new(created_path(&reply)?)
+    }
+
+    pub fn delete_call(&self, call_id: impl Display) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let call_id = call_id.to_string();
+        run_fun!(
+            mmcli -m $dbus_path --voice-delete-call=$call_id
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn hangup_all(&self) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --voice-hangup-all
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call() {
+        let json = r#"{"call":{"dbus-path":"/org/freedesktop/ModemManager1/Call/2","properties":{"audio-format":{"encoding":"--","rate":"--","resolution":"--"},"audio-port":"--","direction":"incoming","multiparty":"no","number":"+8613800000000","state":"ringing-in","state-reason":"incoming-new"}}}"#;
+        let call_info: CallInfo = json.parse().unwrap();
+        assert_eq!(call_info.state(), CallState::RingingIn);
+        assert!("--".parse::<CallState>().is_err());
+        assert_eq!(call_info.call.properties.number, "+8613800000000");
+        assert!(call_info.send_dtmf("12x").is_err());
+        let list: CallList = "{}".parse().unwrap();
+        assert!(list.call_list.is_empty());
+    }
+}
@@ -4,6 +4,7 @@ use super::{
     bearer::BearerInfo,
     list::ModemIDList,
     modem::{ModemInfo, PortList},
+    signal::Signal,
 };
 use crate::iproute2::DeviceList;
 
@@ -17,6 +18,8 @@ pub struct ModemDevice {
     pub sim_ip: String,
     pub modem: ModemInfo,
     pub bearer: Vec<BearerInfo>,
+    /// Empty unless extended signal information is set up
+    pub signal: Signal,
 }
 
 impl ModemDevice {
@@ -32,10 +35,10 @@ impl ModemDevice {
                 modem_device.net_ip = device.get_ip_addr();
             }
             None => {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Device {} not found", modem_device.device_name),
-                ));
+                return Err(io::Error::other(format!(
+                    "Device {} not found",
+                    modem_device.device_name
+                )));
             }
         }
         modem_device.device_name = modem_info.net_device_name();
@@ -45,6 +48,7 @@ impl ModemDevice {
             modem_device.sim_ip = bearer.bearer.ipv4_config.address.to_string();
             modem_device.bearer.push(bearer);
         }
+        modem_device.signal = modem_info.signal().unwrap_or_default();
         modem_device.modem = modem_info;
         Ok(modem_device)
     }
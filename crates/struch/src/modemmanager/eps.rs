@@ -0,0 +1,82 @@
+use std::io;
+
+use cmd_lib::run_fun;
+
+use super::{
+    bearer::{Auth, BearerInfo, BearerProperties},
+    modem::ModemInfo,
+};
+
+/// Keys accepted by `--3gpp-set-initial-eps-bearer-settings`.
+fn initial_bearer_args(settings: &BearerProperties) -> io::Result<String> {
+    BearerProperties {
+        apn: settings.apn.clone(),
+        ip_type: settings.ip_type,
+        user: settings.user.clone(),
+        password: settings.password.clone(),
+        allowed_auth: settings.allowed_auth.clone(),
+        ..Default::default()
+    }
+    .to_args()
+}
+
+impl ModemInfo {
+    /// Configure the APN, ip type and credentials used to attach to LTE.
+    ///
+    /// Only apn, ip_type, user, password and allowed_auth are used, an empty
+    /// apn lets the network choose.
+    pub fn set_initial_eps_bearer(&self, settings: &BearerProperties) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = initial_bearer_args(settings)?;
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-set-initial-eps-bearer-settings=$args
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// The bearer the modem actually attached with, which may differ from
+    /// the configured settings when the network overrides the APN.
+    pub fn initial_eps_bearer(&self) -> io::Result<Option<BearerInfo>> {
+        match self.modem.n3gpp.eps.initial_bearer.dbus_path.as_str() {
+            "" | "--" | "/" => Ok(None),
+            dbus_path => BearerInfo::new(dbus_path).map(Some),
+        }
+    }
+
+    /// The allowed auth methods of the initial bearer, which the modem
+    /// settings don't report.
+    pub fn initial_eps_auth(&self) -> io::Result<Vec<Auth>> {
+        let Some(bearer_info) = self.initial_eps_bearer()? else {
+            return Ok(Vec::new());
+        };
+        Ok(bearer_info
+            .bearer
+            .properties
+            .allowed_auth
+            .iter()
+            .filter_map(|auth| auth.as_str())
+            .flat_map(|auth| auth.split(", "))
+            .filter_map(|auth| auth.trim().parse().ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_bearer_args() {
+        let settings = BearerProperties {
+            user: "user".to_string(),
+            profile_id: Some(3),
+            allow_roaming: Some(true),
+            ..BearerProperties::new("ims")
+        };
+        assert_eq!(
+            initial_bearer_args(&settings).unwrap(),
+            "apn='ims',user='user'"
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    fmt::Display,
+    io,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use cmd_lib::run_fun;
+
+use super::{
+    list::ModemIDList,
+    modem::ModemInfo,
+    sms::{SmsInfo, SmsList},
+};
+
+/// Signal quality changes smaller than this many percent are not reported.
+const SIGNAL_STEP: u32 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModemEvent {
+    StateChanged {
+        old: String,
+        new: String,
+        /// `state-failed-reason`, `--` unless the modem failed
+        reason: String,
+    },
+    RegistrationChanged {
+        old: String,
+        new: String,
+        operator_code: String,
+    },
+    /// Signal quality in percent
+    SignalChanged {
+        old: u32,
+        new: u32,
+    },
+    BearerConnected {
+        path: String,
+        interface: String,
+    },
+    BearerDisconnected {
+        path: String,
+    },
+    SmsReceived {
+        path: String,
+    },
+    /// The modem is gone, no more events are sent.
+    Removed,
+}
+
+impl Display for ModemEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModemEvent::StateChanged { old, new, reason }
+                if !reason.is_empty() && reason != "--" =>
+            {
+                write!(f, "state: {} -> {} ({})", old, new, reason)
+            }
+            ModemEvent::StateChanged { old, new, .. } => write!(f, "state: {} -> {}", old, new),
+            ModemEvent::RegistrationChanged {
+                old,
+                new,
+                operator_code,
+            } => write!(f, "registration: {} -> {} [{}]", old, new, operator_code),
+            ModemEvent::SignalChanged { old, new } => write!(f, "signal: {}% -> {}%", old, new),
+            ModemEvent::BearerConnected { path, interface } => {
+                write!(f, "bearer connected: {} on {}", path, interface)
+            }
+            ModemEvent::BearerDisconnected { path } => write!(f, "bearer disconnected: {}", path),
+            ModemEvent::SmsReceived { path } => write!(f, "sms received: {}", path),
+            ModemEvent::Removed => write!(f, "modem removed"),
+        }
+    }
+}
+
+/// The part of the modem state that events are derived from.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub state: String,
+    pub state_failed_reason: String,
+    pub registration_state: String,
+    pub operator_code: String,
+    pub signal_quality: u32,
+    /// Dbus path and interface of the connected bearers
+    pub bearers: Vec<(String, String)>,
+    pub sms: Vec<String>,
+}
+
+impl Snapshot {
+    pub fn new(modem_info: &ModemInfo) -> io::Result<Snapshot> {
+        let dbus_path = modem_info.modem.dbus_path.clone();
+        // messaging is unavailable while the modem is disabled
+        let sms_list: SmsList = run_fun!(
+            mmcli -m $dbus_path --messaging-list-sms -J
+        )
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_default();
+        let bearers = modem_info
+            .bearers()?
+            .into_iter()
+            .filter(|b| b.is_connected())
+            .map(|b| (b.bearer.dbus_path, b.bearer.status.interface))
+            .collect();
+        let generic = &modem_info.modem.generic;
+        Ok(Snapshot {
+            state: generic.state.clone(),
+            state_failed_reason: generic.state_failed_reason.clone(),
+            registration_state: modem_info.modem.n3gpp.registration_state.clone(),
+            operator_code: modem_info.modem.n3gpp.operator_code.clone(),
+            signal_quality: generic.signal_quality.value.parse().unwrap_or(0),
+            bearers,
+            sms: sms_list.sms_list,
+        })
+    }
+
+    /// The events that lead from `self` to `new`.
+    pub fn diff(&self, new: &Snapshot) -> Vec<ModemEvent> {
+        let mut events = Vec::new();
+        if self.state != new.state {
+            events.push(ModemEvent::StateChanged {
+                old: self.state.clone(),
+                new: new.state.clone(),
+                reason: new.state_failed_reason.clone(),
+            });
+        }
+        if self.registration_state != new.registration_state
+            || self.operator_code != new.operator_code
+        {
+            events.push(ModemEvent::RegistrationChanged {
+                old: self.registration_state.clone(),
+                new: new.registration_state.clone(),
+                operator_code: new.operator_code.clone(),
+            });
+        }
+        if self.signal_quality.abs_diff(new.signal_quality) >= SIGNAL_STEP {
+            events.push(ModemEvent::SignalChanged {
+                old: self.signal_quality,
+                new: new.signal_quality,
+            });
+        }
+        for (path, _) in self.bearers.iter() {
+            if !new.bearers.iter().any(|(p, _)| p == path) {
+                events.push(ModemEvent::BearerDisconnected { path: path.clone() });
+            }
+        }
+        for (path, interface) in new.bearers.iter() {
+            if !self.bearers.iter().any(|(p, _)| p == path) {
+                events.push(ModemEvent::BearerConnected {
+                    path: path.clone(),
+                    interface: interface.clone(),
+                });
+            }
+        }
+        for path in new.sms.iter() {
+            if !self.sms.contains(path) {
+                events.push(ModemEvent::SmsReceived { path: path.clone() });
+            }
+        }
+        events
+    }
+}
+
+impl ModemInfo {
+    /// Poll the modem every `interval` and send the changes as events.
+    ///
+    /// The polling thread stops when the receiver is dropped or the modem
+    /// goes away, in which case `ModemEvent::Removed` is the last event.
+    /// A poll that fails while the modem is still listed is skipped.
+    pub fn subscribe(&self, interval: Duration) -> io::Result<Receiver<ModemEvent>> {
+        let mut modem_info = self.clone();
+        let mut last = Snapshot::new(&modem_info)?;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || loop {
+            thread::sleep(interval);
+            let snapshot = match modem_info.refresh() {
+                Ok(()) => Snapshot::new(&modem_info),
+                Err(e) => Err(e),
+            };
+            let Ok(mut snapshot) = snapshot else {
+                let removed = ModemIDList::new()
+                    .is_ok_and(|list| !list.modem_id_list.contains(&modem_info.modem.dbus_path));
+                if removed {
+                    let _ = tx.send(ModemEvent::Removed);
+                    return;
+                }
+                continue;
+            };
+            let events = last.diff(&snapshot);
+            for event in events.iter() {
+                // only report messages delivered to us, not the ones we send
+                if let ModemEvent::SmsReceived { path } = event {
+                    match SmsInfo::new(path) {
+                        Ok(sms_info) if sms_info.sms.properties.pdu_type == "deliver" => {}
+                        _ => continue,
+                    }
+                }
+                if tx.send(event.clone()).is_err() {
+                    return;
+                }
+            }
+            // small signal changes add up until they are reported
+            if !events
+                .iter()
+                .any(|e| matches!(e, ModemEvent::SignalChanged { .. }))
+            {
+                snapshot.signal_quality = last.signal_quality;
+            }
+            last = snapshot;
+        });
+        Ok(rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old = Snapshot {
+            state: "registered".to_string(),
+            registration_state: "home".to_string(),
+            operator_code: "46000".to_string(),
+            signal_quality: 60,
+            bearers: vec![("/bearer/0".to_string(), "wwan0".to_string())],
+            ..Default::default()
+        };
+        let mut new = old.clone();
+        new.signal_quality = 62;
+        assert!(old.diff(&new).is_empty());
+        new.state = "connected".to_string();
+        new.signal_quality = 40;
+        new.bearers = vec![("/bearer/1".to_string(), "wwan0".to_string())];
+        new.sms = vec!["/sms/3".to_string()];
+        let events = old.diff(&new);
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0].to_string(), "state: registered -> connected");
+        assert_eq!(events[1], ModemEvent::SignalChanged { old: 60, new: 40 });
+        assert_eq!(
+            events[2],
+            ModemEvent::BearerDisconnected {
+                path: "/bearer/0".to_string()
+            }
+        );
+        assert_eq!(
+            events[4],
+            ModemEvent::SmsReceived {
+                path: "/sms/3".to_string()
+            }
+        );
+    }
+}
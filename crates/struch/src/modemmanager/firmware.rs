@@ -0,0 +1,242 @@
+use std::{io, str::FromStr, time::Duration};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    mmcli::parse_key_values,
+    modem::{ModemInfo, PortList},
+};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareInfo {
+    pub modem: FirmwareModem,
+}
+
+impl FromStr for FirmwareInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareModem {
+    pub firmware: FirmwareList,
+}
+
+/// Modems without firmware management report no list at all.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FirmwareList {
+    pub list: Vec<String>,
+}
+
+/// One firmware image installed on the modem.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Firmware {
+    pub unique_id: String,
+    /// `generic` or `gobi`
+    pub image_type: String,
+    pub current: bool,
+}
+
+impl FromStr for Firmware {
+    type Err = String;
+
+    /// Parse one `--firmware-list` entry:
+    /// `unique-id: 02.14.03.00_ATT, image-type: gobi, current: yes`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut firmware = Firmware::default();
+        for (key, value) in parse_key_values(s) {
+            match key.as_str() {
+                "unique-id" => firmware.unique_id = value,
+                "image-type" | "type" => firmware.image_type = value,
+                "current" | "is-current" => firmware.current = value == "yes",
+                _ => {}
+            }
+        }
+        if firmware.unique_id.is_empty() {
+            return Err(format!("Invalid firmware entry: {}", s));
+        }
+        Ok(firmware)
+    }
+}
+
+/// One carrier configuration image (MBN) as listed by the QMI persistent
+/// device configuration service.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CarrierConfig {
+    pub description: String,
+    pub version: String,
+    /// Hexadecimal config id used to select the image
+    pub id: String,
+    pub active: bool,
+}
+
+impl CarrierConfig {
+    /// Parse `qmicli --pdc-list-configs` output.
+    pub fn parse_list(s: &str) -> Vec<CarrierConfig> {
+        let mut configs: Vec<CarrierConfig> = Vec::new();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.starts_with("Config #") {
+                configs.push(CarrierConfig::default());
+                continue;
+            }
+            let (Some(config), Some((key, value))) = (configs.last_mut(), line.split_once(':'))
+            else {
+                continue;
+            };
+            let value = value.trim().trim_matches('\'').to_string();
+            match key.trim() {
+                "Description" => config.description = value,
+                "Version" => config.version = value,
+                "ID" => config.id = value.replace(':', ""),
+                "Status" => config.active = value.eq_ignore_ascii_case("active"),
+                _ => {}
+            }
+        }
+        configs
+    }
+}
+
+impl ModemInfo {
+    pub fn firmware_list(&self) -> io::Result<Vec<Firmware>> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let info: FirmwareInfo = run_fun!(
+            mmcli -m $dbus_path --firmware-list -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        info.modem
+            .firmware
+            .list
+            .iter()
+            .map(|f| f.parse().map_err(io::Error::other))
+            .collect()
+    }
+
+    /// Select another firmware image, the modem reboots into it and is
+    /// returned once ModemManager has probed it again.
+    pub fn select_firmware(&self, unique_id: &str, timeout: Duration) -> io::Result<ModemInfo> {
+        if !self
+            .firmware_list()?
+            .iter()
+            .any(|f| f.unique_id == unique_id)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No firmware image {}", unique_id),
+            ));
+        }
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --firmware-select=$unique_id
+        )
+        .map_err(io::Error::other)?;
+        self.wait_reprobe(timeout, |modem_info| {
+            modem_info.modem.dbus_path != dbus_path
+        })
+    }
+
+    fn control_port(&self) -> io::Result<String> {
+        let port = PortList::from_vec_string(&self.modem.generic.ports).get_control_name();
+        if port.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Carrier configurations need a QMI or MBIM port",
+            ));
+        }
+        Ok(format!("/dev/{}", port))
+    }
+
+    /// Carrier configurations through qmicli, going via qmi-proxy so
+    /// ModemManager keeps its port open.
+    pub fn carrier_configs(&self) -> io::Result<Vec<CarrierConfig>> {
+        let port = self.control_port()?;
+        let output = run_fun!(
+            qmicli -d $port -p --pdc-list-configs=software
+        )
+        .map_err(io::Error::other)?;
+        let mut configs = CarrierConfig::parse_list(&output);
+        // older qmicli releases do not print the status
+        let current = &self.modem.generic.carrier_configuration;
+        if !configs.iter().any(|c| c.active) {
+            for config in configs.iter_mut() {
+                config.active = config.description == *current;
+            }
+        }
+        Ok(configs)
+    }
+
+    /// Activate another carrier configuration, the modem resets to load it
+    /// and is returned once ModemManager has probed it again.
+    pub fn select_carrier_config(&self, id: &str, timeout: Duration) -> io::Result<ModemInfo> {
+        let configs = self.carrier_configs()?;
+        let id = id.trim_start_matches("0x").to_uppercase();
+        let config = configs
+            .iter()
+            .find(|c| c.id.trim_start_matches("0x").to_uppercase() == id)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("No carrier configuration {}", id),
+                )
+            })?;
+        let port = self.control_port()?;
+        let selected = format!("software,{}", config.id);
+        run_fun!(
+            qmicli -d $port -p --pdc-set-selected-config=$selected;
+            qmicli -d $port -p --pdc-activate-config=software
+        )
+        .map_err(io::Error::other)?;
+        let dbus_path = self.modem.dbus_path.clone();
+        self.wait_reprobe(timeout, |modem_info| {
+            modem_info.modem.dbus_path != dbus_path
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_carrier_configs() {
+        let output = "[/dev/cdc-wdm0] 2 configs loaded
+\tConfig type: 'software'
+\t\tConfig #1:
+\t\t\tDescription: 'ROW_Generic_3GPP'
+\t\t\tVersion: 0x05010820
+\t\t\tStatus: 'Active'
+\t\t\tID: '0A:01:00:00'
+\t\tConfig #2:
+\t\t\tDescription: 'Commercial-CMCC'
+\t\t\tVersion: 0x05010E17
+\t\t\tStatus: 'Inactive'
+\t\t\tID: '0B:02:00:00'
+";
+        let configs = CarrierConfig::parse_list(output);
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].description, "ROW_Generic_3GPP");
+        assert!(configs[0].active);
+        assert_eq!(configs[1].id, "0B020000");
+        assert!(!configs[1].active);
+    }
+
+    #[test]
+    fn test_firmware() {
+        let firmware: Firmware = "unique-id: 02.14.03.00_ATT, image-type: gobi, current: yes"
+            .parse()
+            .unwrap();
+        assert_eq!(firmware.unique_id, "02.14.03.00_ATT");
+        assert_eq!(firmware.image_type, "gobi");
+        assert!(firmware.current);
+        assert!("image-type: generic".parse::<Firmware>().is_err());
+    }
+}
@@ -30,9 +30,9 @@ impl ModemIDList {
     #[rustfmt::skip]
     pub fn new() -> io::Result<ModemIDList> {
         run_fun!(mmcli -L -J) // can not format
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+            .map_err(io::Error::other)?
             .parse()
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+            .map_err(io::Error::other)
     }
 
     pub fn get_modem_list(&self) -> io::Result<Vec<ModemInfo>> {
@@ -0,0 +1,252 @@
+use std::{fmt::Display, io, str::FromStr};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::modem::ModemInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocationSource {
+    /// 3GPP location area, tracking area and cell id
+    N3gpp,
+    GpsRaw,
+    GpsNmea,
+    /// Assisted GPS, mobile station assisted
+    AgpsMsa,
+    /// Assisted GPS, mobile station based
+    AgpsMsb,
+    CdmaBs,
+}
+
+impl LocationSource {
+    fn flag(&self) -> &'static str {
+        match self {
+            LocationSource::N3gpp => "3gpp",
+            LocationSource::GpsRaw => "gps-raw",
+            LocationSource::GpsNmea => "gps-nmea",
+            LocationSource::AgpsMsa => "agps-msa",
+            LocationSource::AgpsMsb => "agps-msb",
+            LocationSource::CdmaBs => "cdma-bs",
+        }
+    }
+}
+
+impl Display for LocationSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.flag())
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationInfo {
+    pub modem: LocationModem,
+}
+
+impl FromStr for LocationInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationModem {
+    pub location: RawLocation,
+}
+
+/// `--location-get` output, every value is a string and `--` when unknown.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RawLocation {
+    #[serde(rename = "3gpp")]
+    pub n3gpp: Location3gpp,
+    #[serde(rename = "cdma-bs")]
+    pub cdma_bs: LocationCdmaBs,
+    pub gps: LocationGps,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location3gpp {
+    pub cid: String,
+    pub lac: String,
+    pub mcc: String,
+    pub mnc: String,
+    pub tac: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationCdmaBs {
+    pub latitude: String,
+    pub longitude: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationGps {
+    pub altitude: String,
+    pub latitude: String,
+    pub longitude: String,
+    pub nmea: Vec<String>,
+    pub utc: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationStatusInfo {
+    pub modem: LocationStatusModem,
+}
+
+impl FromStr for LocationStatusInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationStatusModem {
+    pub location: LocationStatus,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocationStatus {
+    pub capabilities: Vec<String>,
+    pub enabled: Vec<String>,
+    pub signals: String,
+}
+
+/// Typed location, `None` where the modem did not report a value.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Location {
+    pub mcc: Option<String>,
+    pub mnc: Option<String>,
+    pub lac: Option<u32>,
+    pub tac: Option<u32>,
+    pub cid: Option<u32>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub altitude: Option<f64>,
+    /// UTC time of the GPS fix as `hhmmss.ss`
+    pub fix_time: Option<String>,
+}
+
+fn known(value: &str) -> Option<String> {
+    match value.trim() {
+        "" | "--" => None,
+        value => Some(value.to_string()),
+    }
+}
+
+/// LAC, TAC and CID are printed in hexadecimal.
+fn hex(value: &str) -> Option<u32> {
+    known(value).and_then(|v| u32::from_str_radix(&v, 16).ok())
+}
+
+fn float(value: &str) -> Option<f64> {
+    known(value).and_then(|v| v.parse().ok())
+}
+
+impl From<&RawLocation> for Location {
+    fn from(raw: &RawLocation) -> Self {
+        let mut location = Location {
+            mcc: known(&raw.n3gpp.mcc),
+            mnc: known(&raw.n3gpp.mnc),
+            lac: hex(&raw.n3gpp.lac).filter(|lac| *lac != 0),
+            tac: hex(&raw.n3gpp.tac).filter(|tac| *tac != 0),
+            cid: hex(&raw.n3gpp.cid),
+            latitude: float(&raw.gps.latitude),
+            longitude: float(&raw.gps.longitude),
+            altitude: float(&raw.gps.altitude),
+            fix_time: known(&raw.gps.utc),
+        };
+        if location.latitude.is_none() {
+            location.latitude = float(&raw.cdma_bs.latitude);
+            location.longitude = float(&raw.cdma_bs.longitude);
+        }
+        location
+    }
+}
+
+impl Location {
+    pub fn has_fix(&self) -> bool {
+        self.latitude.is_some() && self.longitude.is_some()
+    }
+}
+
+impl ModemInfo {
+    pub fn location_enable(&self, sources: &[LocationSource]) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let flags = sources
+            .iter()
+            .map(|source| format!("--location-enable-{}", source.flag()))
+            .collect::<Vec<String>>();
+        run_fun!(
+            mmcli -m $dbus_path $[flags]
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn location_disable(&self, sources: &[LocationSource]) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let flags = sources
+            .iter()
+            .map(|source| format!("--location-disable-{}", source.flag()))
+            .collect::<Vec<String>>();
+        run_fun!(
+            mmcli -m $dbus_path $[flags]
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn location_status(&self) -> io::Result<LocationStatus> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let info: LocationStatusInfo = run_fun!(
+            mmcli -m $dbus_path --location-status -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        Ok(info.modem.location)
+    }
+
+    pub fn location(&self) -> io::Result<Location> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let info: LocationInfo = run_fun!(
+            mmcli -m $dbus_path --location-get -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        Ok(Location::from(&info.modem.location))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location() {
+        let json = r#"{"modem":{"location":{"3gpp":{"cid":"0A2B3C4D","lac":"0000","mcc":"460","mnc":"00","tac":"5A1B"},"cdma-bs":{"latitude":"--","longitude":"--"},"gps":{"altitude":"41.2","latitude":"39.904200","longitude":"116.407400","nmea":[],"utc":"083015.00"}}}}"#;
+        let info: LocationInfo = json.parse().unwrap();
+        let location = Location::from(&info.modem.location);
+        assert_eq!(location.mcc.as_deref(), Some("460"));
+        assert_eq!(location.mnc.as_deref(), Some("00"));
+        assert_eq!(location.lac, None);
+        assert_eq!(location.tac, Some(0x5A1B));
+        assert_eq!(location.cid, Some(0x0A2B3C4D));
+        assert_eq!(location.latitude, Some(39.9042));
+        assert_eq!(location.altitude, Some(41.2));
+        assert!(location.has_fix());
+    }
+}
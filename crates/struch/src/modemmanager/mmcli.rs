@@ -0,0 +1,87 @@
+//! Helpers for the textual argument and reply formats of mmcli.
+use std::io;
+
+/// Build a `key=value,key=value` argument as accepted by mmcli options like
+/// `--simple-connect` or `--messaging-create-sms`.
+///
+/// Values are quoted so they may contain commas and spaces, pairs with an
+/// empty value are skipped. mmcli has no escapes, so a value holding both
+/// quote characters is rejected.
+pub fn key_values(pairs: &[(&str, String)]) -> io::Result<String> {
+    pairs
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(
+            |(key, value)| match (value.contains('\''), value.contains('"')) {
+                (true, true) => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("The {} value can't contain both ' and \"", key),
+                )),
+                (true, false) => Ok(format!("{}=\"{}\"", key, value)),
+                _ => Ok(format!("{}='{}'", key, value)),
+            },
+        )
+        .collect::<io::Result<Vec<String>>>()
+        .map(|args| args.join(","))
+}
+
+/// Parse a `key: value, key: value` entry as printed in list outputs such
+/// as `--3gpp-scan`.
+///
+/// Values may themselves be comma separated lists, a segment without a
+/// `key:` prefix continues the previous value.
+pub fn parse_key_values(s: &str) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for segment in s.split(", ") {
+        match segment.split_once(": ") {
+            Some((key, value)) if !key.contains(' ') => {
+                pairs.push((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => match pairs.last_mut() {
+                Some((_, value)) => {
+                    value.push_str(", ");
+                    value.push_str(segment.trim());
+                }
+                None => continue,
+            },
+        }
+    }
+    pairs
+}
+
+/// Extract the object path from replies like
+/// `Successfully created new SMS: /org/freedesktop/ModemManager1/SMS/3`.
+pub fn created_path(reply: &str) -> io::Result<String> {
+    reply
+        .split_whitespace()
+        .find(|word| word.starts_with("/org/freedesktop/ModemManager1/"))
+        .map(|path| path.to_string())
+        .ok_or_else(|| io::Error::other(format!("Unexpected mmcli reply: {}", reply)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_values() {
+        let args = key_values(&[
+            ("number", "+8613800000000".to_string()),
+            ("text", "it's, fine".to_string()),
+            ("smsc", String::new()),
+        ]);
+        assert_eq!(args.unwrap(), "number='+8613800000000',text=\"it's, fine\"");
+        assert!(key_values(&[("text", "it's \"quoted\"".to_string())]).is_err());
+        let pairs =
+            parse_key_values("operator-code: 46000, access-technologies: umts, lte, name: CMCC");
+        assert_eq!(pairs[0], ("operator-code".to_string(), "46000".to_string()));
+        assert_eq!(
+            pairs[1],
+            ("access-technologies".to_string(), "umts, lte".to_string())
+        );
+        assert_eq!(pairs[2], ("name".to_string(), "CMCC".to_string()));
+        let path =
+            created_path("Successfully created new SMS: /org/freedesktop/ModemManager1/SMS/3");
+        assert_eq!(path.unwrap(), "/org/freedesktop/ModemManager1/SMS/3");
+    }
+}
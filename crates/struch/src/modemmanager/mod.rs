@@ -2,3 +2,20 @@ pub mod device;
 pub mod list;
 pub mod bearer;
 pub mod modem;
+pub mod mmcli;
+pub mod sms;
+pub mod ussd;
+pub mod location;
+pub mod signal;
+pub mod sim;
+pub mod modes;
+pub mod operator;
+pub mod power;
+pub mod firmware;
+pub mod profile;
+pub mod eps;
+pub mod packet;
+pub mod nr5g;
+pub mod time;
+pub mod call;
+pub mod events;
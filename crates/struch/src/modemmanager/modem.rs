@@ -1,9 +1,15 @@
-use std::{fmt::Display, io, str::FromStr};
+use std::{fmt::Display, io, str::FromStr, thread, time::Duration};
 
 use cmd_lib::run_fun;
 use serde_derive::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::{
+    bearer::BearerProperties,
+    list::ModemIDList,
+    modes::{Band, ModeCombination},
+};
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModemInfo {
@@ -24,14 +30,34 @@ impl Display for ModemInfo {
     }
 }
 
+/// Call `poll` every second until it returns a value, `None` once
+/// `timeout` has passed without one.
+pub fn poll_until<T>(
+    timeout: Duration,
+    mut poll: impl FnMut() -> io::Result<Option<T>>,
+) -> io::Result<Option<T>> {
+    let step = Duration::from_secs(1);
+    let mut waited = Duration::ZERO;
+    loop {
+        if let Some(value) = poll()? {
+            return Ok(Some(value));
+        }
+        if waited >= timeout {
+            return Ok(None);
+        }
+        thread::sleep(step);
+        waited += step;
+    }
+}
+
 impl ModemInfo {
     pub fn new(modem_id: impl Display) -> io::Result<ModemInfo> {
         run_fun!(
             mmcli -m $modem_id -J
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+        .map_err(io::Error::other)?
         .parse()
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
     }
 
     pub fn net_device_name(&self) -> String {
@@ -44,17 +70,12 @@ impl ModemInfo {
         run_fun!(
             mmcli -m $dbus_path --simple-disconnect
         )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+        .map_err(io::Error::other)
         .map(|_| ())
     }
 
     pub fn connect(&self, apn: &str) -> io::Result<()> {
-        let dbus_path = self.modem.dbus_path.clone();
-        run_fun!(
-            mmcli -m $dbus_path --simple-connect="apn=$apn"
-        )
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
-        .map(|_| ())
+        self.connect_with(&BearerProperties::new(apn))
     }
 
     pub fn refresh(&mut self) -> io::Result<()> {
@@ -62,6 +83,36 @@ impl ModemInfo {
         self.modem = Self::new(dbus_path)?.modem;
         Ok(())
     }
+
+    /// Wait up to `timeout` for ModemManager to probe the modem again after
+    /// an operation that makes it reappear under a new index.
+    ///
+    /// The modem is matched by its physical device path, `ready` decides
+    /// whether the probed modem is the expected one.
+    pub fn wait_reprobe(
+        &self,
+        timeout: Duration,
+        ready: impl Fn(&ModemInfo) -> bool,
+    ) -> io::Result<ModemInfo> {
+        let device = self.modem.generic.device.clone();
+        let found = poll_until(timeout, || {
+            // the list is briefly empty or stale while the modem re-probes
+            let Ok(id_list) = ModemIDList::new() else {
+                return Ok(None);
+            };
+            Ok(id_list
+                .modem_id_list
+                .iter()
+                .filter_map(|id| ModemInfo::new(id).ok())
+                .find(|modem_info| modem_info.modem.generic.device == device && ready(modem_info)))
+        })?;
+        found.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("Modem {} did not come back", device),
+            )
+        })
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -172,11 +223,11 @@ pub struct Generic {
     #[serde(rename = "carrier-configuration-revision")]
     pub carrier_configuration_revision: String,
     #[serde(rename = "current-bands")]
-    pub current_bands: Vec<Value>,
+    pub current_bands: Vec<Band>,
     #[serde(rename = "current-capabilities")]
     pub current_capabilities: Vec<String>,
     #[serde(rename = "current-modes")]
-    pub current_modes: String,
+    pub current_modes: ModeCombination,
     pub device: String,
     #[serde(rename = "device-identifier")]
     pub device_identifier: String,
@@ -202,18 +253,18 @@ pub struct Generic {
     pub signal_quality: SignalQuality,
     pub sim: String,
     #[serde(rename = "sim-slots")]
-    pub sim_slots: Vec<Value>,
+    pub sim_slots: Vec<String>,
     pub state: String,
     #[serde(rename = "state-failed-reason")]
     pub state_failed_reason: String,
     #[serde(rename = "supported-bands")]
-    pub supported_bands: Vec<Value>,
+    pub supported_bands: Vec<Band>,
     #[serde(rename = "supported-capabilities")]
     pub supported_capabilities: Vec<String>,
     #[serde(rename = "supported-ip-families")]
     pub supported_ip_families: Vec<String>,
     #[serde(rename = "supported-modes")]
-    pub supported_modes: Vec<String>,
+    pub supported_modes: Vec<ModeCombination>,
     #[serde(rename = "unlock-required")]
     pub unlock_required: String,
     #[serde(rename = "unlock-retries")]
@@ -255,6 +306,15 @@ impl PortList {
             .map(|port| port.name.clone())
             .unwrap_or_else(|| "".to_string())
     }
+
+    /// The QMI or MBIM control port, e.g. `cdc-wdm0`.
+    pub fn get_control_name(&self) -> String {
+        self.ports
+            .iter()
+            .find(|port| matches!(port.device_type, DeviceType::Qmi | DeviceType::Mbim))
+            .map(|port| port.name.clone())
+            .unwrap_or_else(|| "".to_string())
+    }
 }
 
 pub struct Port {
@@ -266,6 +326,7 @@ pub struct Port {
 pub enum DeviceType {
     Net,
     Mbim,
+    Qmi,
     Unknown,
 }
 
@@ -276,6 +337,7 @@ impl FromStr for DeviceType {
         match s {
             "(net)" => Ok(DeviceType::Net),
             "(mbim)" => Ok(DeviceType::Mbim),
+            "(qmi)" => Ok(DeviceType::Qmi),
             _ => Ok(DeviceType::Unknown),
         }
     }
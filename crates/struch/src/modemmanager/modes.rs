@@ -0,0 +1,281 @@
+use std::{fmt::Display, io, str::FromStr};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::modem::ModemInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Mode {
+    Cs,
+    G2,
+    G3,
+    G4,
+    G5,
+    Any,
+    None,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "cs" => Ok(Mode::Cs),
+            "2g" => Ok(Mode::G2),
+            "3g" => Ok(Mode::G3),
+            "4g" => Ok(Mode::G4),
+            "5g" => Ok(Mode::G5),
+            "any" => Ok(Mode::Any),
+            "none" => Ok(Mode::None),
+            _ => Err(format!("Invalid mode: {}", s)),
+        }
+    }
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mode::Cs => write!(f, "cs"),
+            Mode::G2 => write!(f, "2g"),
+            Mode::G3 => write!(f, "3g"),
+            Mode::G4 => write!(f, "4g"),
+            Mode::G5 => write!(f, "5g"),
+            Mode::Any => write!(f, "any"),
+            Mode::None => write!(f, "none"),
+        }
+    }
+}
+
+/// Allowed and preferred modes as printed by mmcli:
+/// `allowed: 3g, 4g; preferred: 4g`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct ModeCombination {
+    pub allowed: Vec<Mode>,
+    pub preferred: Option<Mode>,
+}
+
+impl FromStr for ModeCombination {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut combination = ModeCombination::default();
+        for part in s.split(';') {
+            match part.trim().split_once(':') {
+                Some(("allowed", modes)) => {
+                    let allowed: Result<Vec<Mode>, String> =
+                        modes.split(',').map(Mode::from_str).collect();
+                    combination.allowed = allowed?;
+                    combination.allowed.sort();
+                }
+                Some(("preferred", mode)) => {
+                    combination.preferred = match mode.parse()? {
+                        Mode::None => None,
+                        mode => Some(mode),
+                    };
+                }
+                _ => return Err(format!("Invalid mode combination: {}", s)),
+            }
+        }
+        Ok(combination)
+    }
+}
+
+impl From<String> for ModeCombination {
+    /// ModemManager prints `--` when the modes are unknown.
+    fn from(s: String) -> Self {
+        s.parse().unwrap_or_default()
+    }
+}
+
+impl From<ModeCombination> for String {
+    fn from(combination: ModeCombination) -> Self {
+        combination.to_string()
+    }
+}
+
+impl Display for ModeCombination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let allowed = self
+            .allowed
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>();
+        let preferred = self.preferred.unwrap_or(Mode::None);
+        write!(
+            f,
+            "allowed: {}; preferred: {}",
+            allowed.join(", "),
+            preferred
+        )
+    }
+}
+
+impl ModeCombination {
+    pub fn new(allowed: &[Mode], preferred: Option<Mode>) -> Self {
+        let mut allowed = allowed.to_vec();
+        allowed.sort();
+        allowed.dedup();
+        ModeCombination { allowed, preferred }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Band {
+    /// GSM, DCS, PCS and the other legacy bands by name
+    Gsm(String),
+    Utran(u16),
+    Eutran(u16),
+    Ngran(u16),
+    Cdma(String),
+    Any,
+    Unknown(String),
+}
+
+impl From<String> for Band {
+    fn from(s: String) -> Self {
+        let number = |prefix: &str| s.strip_prefix(prefix).and_then(|n| n.parse().ok());
+        if let Some(n) = number("utran-") {
+            return Band::Utran(n);
+        }
+        if let Some(n) = number("eutran-") {
+            return Band::Eutran(n);
+        }
+        if let Some(n) = number("ngran-") {
+            return Band::Ngran(n);
+        }
+        match s.as_str() {
+            "any" => Band::Any,
+            "egsm" | "dcs" | "pcs" | "g850" | "g450" | "g480" | "g750" | "g380" | "g410"
+            | "g710" | "g810" => Band::Gsm(s),
+            _ if s.starts_with("cdma-") => Band::Cdma(s),
+            _ => Band::Unknown(s),
+        }
+    }
+}
+
+impl FromStr for Band {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Band::from(s.trim().to_string()) {
+            Band::Unknown(s) => Err(format!("Invalid band: {}", s)),
+            band => Ok(band),
+        }
+    }
+}
+
+impl From<Band> for String {
+    fn from(band: Band) -> Self {
+        band.to_string()
+    }
+}
+
+impl Display for Band {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Band::Gsm(name) | Band::Cdma(name) | Band::Unknown(name) => write!(f, "{}", name),
+            Band::Utran(n) => write!(f, "utran-{}", n),
+            Band::Eutran(n) => write!(f, "eutran-{}", n),
+            Band::Ngran(n) => write!(f, "ngran-{}", n),
+            Band::Any => write!(f, "any"),
+        }
+    }
+}
+
+fn join<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<String>>()
+        .join("|")
+}
+
+impl ModemInfo {
+    /// Set the allowed and preferred modes, which must be one of the
+    /// combinations in `Generic.supported_modes`.
+    pub fn set_modes(&self, modes: &ModeCombination) -> io::Result<()> {
+        let supported = &self.modem.generic.supported_modes;
+        if !supported.iter().any(|s| s == modes) {
+            let supported = supported
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<String>>();
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Unsupported modes '{}', supported:\n  {}",
+                    modes,
+                    supported.join("\n  ")
+                ),
+            ));
+        }
+        let dbus_path = self.modem.dbus_path.clone();
+        let allowed = join(&modes.allowed);
+        let preferred = modes.preferred.unwrap_or(Mode::None).to_string();
+        run_fun!(
+            mmcli -m $dbus_path --set-allowed-modes=$allowed --set-preferred-mode=$preferred
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Restrict the current bands, `[Band::Any]` restores all supported bands.
+    pub fn set_bands(&self, bands: &[Band]) -> io::Result<()> {
+        let supported = &self.modem.generic.supported_bands;
+        let unsupported = bands
+            .iter()
+            .filter(|band| **band != Band::Any && !supported.contains(band))
+            .map(|band| band.to_string())
+            .collect::<Vec<String>>();
+        if bands.is_empty() || !unsupported.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Unsupported bands '{}', supported: {}",
+                    unsupported.join(", "),
+                    join(supported).replace('|', ", ")
+                ),
+            ));
+        }
+        let dbus_path = self.modem.dbus_path.clone();
+        let bands = join(bands);
+        run_fun!(
+            mmcli -m $dbus_path --set-current-bands=$bands
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modes() {
+        let modes: ModeCombination = "allowed: 4g, 3g; preferred: 4g".parse().unwrap();
+        assert_eq!(
+            modes,
+            ModeCombination::new(&[Mode::G3, Mode::G4], Some(Mode::G4))
+        );
+        assert_eq!(modes.to_string(), "allowed: 3g, 4g; preferred: 4g");
+        let modes: ModeCombination = "allowed: 4g; preferred: none".parse().unwrap();
+        assert_eq!(modes.preferred, None);
+        assert_eq!(
+            ModeCombination::from("--".to_string()),
+            ModeCombination::default()
+        );
+    }
+
+    #[test]
+    fn test_bands() {
+        assert_eq!("eutran-3".parse(), Ok(Band::Eutran(3)));
+        assert_eq!("ngran-78".parse(), Ok(Band::Ngran(78)));
+        assert_eq!("egsm".parse(), Ok(Band::Gsm("egsm".to_string())));
+        assert!("eutran-x".parse::<Band>().is_err());
+        assert_eq!(Band::Utran(1).to_string(), "utran-1");
+    }
+}
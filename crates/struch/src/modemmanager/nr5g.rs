@@ -0,0 +1,131 @@
+use std::{fmt::Display, io};
+
+use cmd_lib::run_fun;
+
+use super::{
+    mmcli,
+    modem::ModemInfo,
+    signal::{Signal, Technology},
+};
+
+/// How the modem uses 5G, derived from the access technologies and the
+/// extended signal information.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Nr5gMode {
+    /// 5G standalone, NR without an LTE anchor
+    Sa,
+    /// 5G non-standalone, NR carrier on an LTE anchor
+    Nsa,
+    Lte,
+    /// Older technologies or not registered
+    Other,
+}
+
+impl Display for Nr5gMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Nr5gMode::Sa => write!(f, "SA"),
+            Nr5gMode::Nsa => write!(f, "NSA"),
+            Nr5gMode::Lte => write!(f, "LTE"),
+            Nr5gMode::Other => write!(f, "--"),
+        }
+    }
+}
+
+impl Nr5gMode {
+    pub fn is_nr(&self) -> bool {
+        matches!(self, Nr5gMode::Sa | Nr5gMode::Nsa)
+    }
+}
+
+/// Nicks of MMModem3gppMicoMode.
+static MICO_MODES: &[&str] = &["unknown", "unsupported", "disabled", "enabled"];
+/// Nicks of MMModem3gppDrxCycle.
+static DRX_CYCLES: &[&str] = &["unknown", "unsupported", "32", "64", "128", "256"];
+
+fn check(kind: &str, value: &str, valid: &[&str]) -> io::Result<()> {
+    if value.is_empty() || valid.contains(&value) {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "Invalid {} {}, expected one of {}",
+            kind,
+            value,
+            valid.join(", ")
+        ),
+    ))
+}
+
+impl ModemInfo {
+    /// Derive the 5G mode, `signal` catches NSA modems that only report
+    /// the LTE anchor as access technology.
+    pub fn nr5g_mode(&self, signal: Option<&Signal>) -> Nr5gMode {
+        let technologies = &self.modem.generic.access_technologies;
+        let has = |tech: &str| {
+            technologies
+                .iter()
+                .any(|t| t.split(", ").any(|t| t == tech))
+        };
+        let nr_signal = signal.is_some_and(|s| s.get(Technology::Nr5g).is_some());
+        match (has("5gnr"), has("lte")) {
+            (true, false) => Nr5gMode::Sa,
+            (true, true) => Nr5gMode::Nsa,
+            (false, true) if nr_signal => Nr5gMode::Nsa,
+            (false, true) => Nr5gMode::Lte,
+            (false, false) => Nr5gMode::Other,
+        }
+    }
+
+    /// Set the MICO mode and DRX cycle, empty values are left unchanged.
+    pub fn set_nr5g_registration_settings(
+        &self,
+        mico_mode: &str,
+        drx_cycle: &str,
+    ) -> io::Result<()> {
+        check("mico mode", mico_mode, MICO_MODES)?;
+        check("drx cycle", drx_cycle, DRX_CYCLES)?;
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = mmcli::key_values(&[
+            ("mico-mode", mico_mode.to_string()),
+            ("drx-cycle", drx_cycle.to_string()),
+        ])?;
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-set-nr5g-registration-settings=$args
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modemmanager::signal::{Metric, MetricKind, TechnologySignal};
+
+    #[test]
+    fn test_nr5g_mode() {
+        let mut modem_info = ModemInfo::default();
+        modem_info.modem.generic.access_technologies = vec!["5gnr".to_string()];
+        assert_eq!(modem_info.nr5g_mode(None), Nr5gMode::Sa);
+        modem_info.modem.generic.access_technologies = vec!["lte, 5gnr".to_string()];
+        assert_eq!(modem_info.nr5g_mode(None), Nr5gMode::Nsa);
+        modem_info.modem.generic.access_technologies = vec!["lte".to_string()];
+        assert_eq!(modem_info.nr5g_mode(None), Nr5gMode::Lte);
+        let signal = Signal {
+            technologies: vec![TechnologySignal {
+                technology: Technology::Nr5g,
+                metrics: vec![Metric {
+                    kind: MetricKind::Rsrp,
+                    value: -90.0,
+                }],
+            }],
+            refresh_rate: 5,
+        };
+        assert_eq!(modem_info.nr5g_mode(Some(&signal)), Nr5gMode::Nsa);
+        assert!(check("drx cycle", "48", DRX_CYCLES).is_err());
+        assert!(check("mico mode", "enabled-sms-only", MICO_MODES).is_err());
+        assert!(check("drx cycle", "unsupported", DRX_CYCLES).is_ok());
+    }
+}
@@ -0,0 +1,178 @@
+use std::{fmt::Display, io, str::FromStr, time::Duration};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{mmcli::parse_key_values, modem::ModemInfo};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanInfo {
+    pub modem: ScanModem,
+}
+
+impl FromStr for ScanInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanModem {
+    #[serde(rename = "3gpp")]
+    pub n3gpp: Scan3gpp,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scan3gpp {
+    #[serde(rename = "scan-networks")]
+    pub scan_networks: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Availability {
+    Available,
+    /// The network the modem is registered in
+    Current,
+    Forbidden,
+    #[default]
+    Unknown,
+}
+
+impl FromStr for Availability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "available" => Ok(Availability::Available),
+            "current" => Ok(Availability::Current),
+            "forbidden" => Ok(Availability::Forbidden),
+            _ => Ok(Availability::Unknown),
+        }
+    }
+}
+
+impl Display for Availability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Availability::Available => write!(f, "available"),
+            Availability::Current => write!(f, "current"),
+            Availability::Forbidden => write!(f, "forbidden"),
+            Availability::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// One network found by a 3GPP scan.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct NetworkOperator {
+    /// MCC and MNC, e.g. `46000`
+    pub code: String,
+    pub name: String,
+    pub access_technologies: Vec<String>,
+    pub availability: Availability,
+}
+
+impl FromStr for NetworkOperator {
+    type Err = String;
+
+    /// Parse one `scan-networks` entry:
+    /// `operator-code: 46000, operator-name: CMCC, access-technologies: lte, availability: current`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut operator = NetworkOperator::default();
+        for (key, value) in parse_key_values(s) {
+            match key.as_str() {
+                "operator-code" => operator.code = value,
+                "operator-name" => operator.name = value,
+                "access-technologies" => {
+                    operator.access_technologies =
+                        value.split(", ").map(|t| t.to_string()).collect()
+                }
+                "availability" => operator.availability = value.parse()?,
+                _ => {}
+            }
+        }
+        if operator.code.is_empty() {
+            return Err(format!("Invalid scan result: {}", s));
+        }
+        Ok(operator)
+    }
+}
+
+/// MCC is 3 digits and MNC 2 or 3 digits.
+fn check_operator_code(code: &str) -> io::Result<()> {
+    if (code.len() == 5 || code.len() == 6) && code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!(
+            "Invalid operator code {}, expected MCC and MNC digits",
+            code
+        ),
+    ))
+}
+
+impl ModemInfo {
+    /// Scan for available networks, this usually takes minutes.
+    pub fn scan_networks(&self, timeout: Duration) -> io::Result<Vec<NetworkOperator>> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let timeout = timeout.as_secs();
+        let info: ScanInfo = run_fun!(
+            mmcli -m $dbus_path --3gpp-scan --timeout=$timeout -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        info.modem
+            .n3gpp
+            .scan_networks
+            .iter()
+            .map(|n| n.parse().map_err(io::Error::other))
+            .collect()
+    }
+
+    /// Register manually in the network `operator_code` (MCC and MNC).
+    pub fn register_in_operator(&self, operator_code: &str) -> io::Result<()> {
+        check_operator_code(operator_code)?;
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-register-in-operator=$operator_code
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Return to automatic network selection.
+    pub fn register_home(&self) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-register-home
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_operator() {
+        let operator: NetworkOperator =
+            "operator-code: 46001, operator-name: CHN-UNICOM, access-technologies: umts, lte, availability: forbidden"
+                .parse()
+                .unwrap();
+        assert_eq!(operator.code, "46001");
+        assert_eq!(operator.name, "CHN-UNICOM");
+        assert_eq!(operator.access_technologies, vec!["umts", "lte"]);
+        assert_eq!(operator.availability, Availability::Forbidden);
+        assert!(check_operator_code("4600").is_err());
+        assert!(check_operator_code("310410").is_ok());
+    }
+}
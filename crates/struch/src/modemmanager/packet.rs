@@ -0,0 +1,129 @@
+use std::{fmt::Display, io, str::FromStr, time::Duration};
+
+use cmd_lib::run_fun;
+
+use super::modem::{poll_until, ModemInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PacketServiceState {
+    Attached,
+    Detached,
+    Unknown,
+}
+
+impl FromStr for PacketServiceState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "attached" => Ok(PacketServiceState::Attached),
+            "detached" => Ok(PacketServiceState::Detached),
+            "unknown" => Ok(PacketServiceState::Unknown),
+            _ => Err(format!("Invalid packet service state: {}", s)),
+        }
+    }
+}
+
+impl Display for PacketServiceState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PacketServiceState::Attached => write!(f, "attached"),
+            PacketServiceState::Detached => write!(f, "detached"),
+            PacketServiceState::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl ModemInfo {
+    pub fn packet_service_state(&self) -> PacketServiceState {
+        self.modem
+            .n3gpp
+            .packet_service_state
+            .parse()
+            .unwrap_or(PacketServiceState::Unknown)
+    }
+
+    /// Registered in the home network or roaming.
+    pub fn is_registered(&self) -> bool {
+        matches!(
+            self.modem.n3gpp.registration_state.as_str(),
+            "home"
+                | "roaming"
+                | "home-sms-only"
+                | "roaming-sms-only"
+                | "home-csfb-not-preferred"
+                | "roaming-csfb-not-preferred"
+        )
+    }
+
+    /// Explicitly attach to or detach from the packet domain.
+    pub fn set_packet_service_state(&self, state: PacketServiceState) -> io::Result<()> {
+        if state == PacketServiceState::Unknown {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Packet service state must be attached or detached",
+            ));
+        }
+        let dbus_path = self.modem.dbus_path.clone();
+        let state = state.to_string();
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-set-packet-service-state=$state
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Wait up to `timeout` for packet service attach.
+    ///
+    /// The error tells a modem that is not registered apart from one that
+    /// is registered but not attached.
+    pub fn wait_attached(&mut self, timeout: Duration) -> io::Result<()> {
+        let attached = poll_until(timeout, || {
+            self.refresh()?;
+            Ok((self.packet_service_state() == PacketServiceState::Attached).then_some(()))
+        })?;
+        if attached.is_some() {
+            return Ok(());
+        }
+        if !self.is_registered() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                format!(
+                    "Not registered, registration state {}",
+                    self.modem.n3gpp.registration_state
+                ),
+            ));
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!(
+                "Registered on {} but packet service is {}",
+                self.modem.n3gpp.operator_name,
+                self.packet_service_state()
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_service_state() {
+        let mut modem_info = ModemInfo::default();
+        modem_info.modem.n3gpp.registration_state = "roaming".to_string();
+        modem_info.modem.n3gpp.packet_service_state = "detached".to_string();
+        assert!(modem_info.is_registered());
+        assert_eq!(
+            modem_info.packet_service_state(),
+            PacketServiceState::Detached
+        );
+        modem_info.modem.n3gpp.packet_service_state = "--".to_string();
+        assert_eq!(
+            modem_info.packet_service_state(),
+            PacketServiceState::Unknown
+        );
+        assert!("--".parse::<PacketServiceState>().is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use std::{fmt::Display, io, str::FromStr, time::Duration};
+
+use cmd_lib::run_fun;
+
+use super::modem::{poll_until, Generic, ModemInfo};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PowerState {
+    On,
+    /// Radio off, the modem stays reachable
+    Low,
+    Off,
+}
+
+impl FromStr for PowerState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "on" => Ok(PowerState::On),
+            "low" => Ok(PowerState::Low),
+            "off" => Ok(PowerState::Off),
+            _ => Err(format!("Invalid power state: {}", s)),
+        }
+    }
+}
+
+impl Display for PowerState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PowerState::On => write!(f, "on"),
+            PowerState::Low => write!(f, "low"),
+            PowerState::Off => write!(f, "off"),
+        }
+    }
+}
+
+impl ModemInfo {
+    pub fn power_state(&self) -> Option<PowerState> {
+        self.modem.generic.power_state.parse().ok()
+    }
+
+    /// Refresh until `done` holds or `timeout` passes.
+    fn wait_state(&mut self, timeout: Duration, done: impl Fn(&Generic) -> bool) -> io::Result<()> {
+        let reached = poll_until(timeout, || {
+            self.refresh()?;
+            if done(&self.modem.generic) {
+                return Ok(Some(()));
+            }
+            if self.modem.generic.state == "failed" {
+                return Err(io::Error::other(format!(
+                    "Modem failed: {}",
+                    self.modem.generic.state_failed_reason
+                )));
+            }
+            Ok(None)
+        })?;
+        reached.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("Modem still {}", self.modem.generic.state),
+            )
+        })
+    }
+
+    /// Enable the modem and wait until it leaves the disabled states.
+    pub fn enable(&mut self, timeout: Duration) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --enable
+        )
+        .map_err(io::Error::other)?;
+        self.wait_state(timeout, |generic| {
+            !matches!(
+                generic.state.as_str(),
+                "disabled" | "disabling" | "enabling"
+            )
+        })
+    }
+
+    pub fn disable(&mut self, timeout: Duration) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --disable
+        )
+        .map_err(io::Error::other)?;
+        self.wait_state(timeout, |generic| generic.state == "disabled")
+    }
+
+    /// Change the power state, the modem is disabled first since
+    /// ModemManager only accepts low and off for disabled modems.
+    pub fn set_power_state(&mut self, state: PowerState, timeout: Duration) -> io::Result<()> {
+        if state != PowerState::On && self.modem.generic.state != "disabled" {
+            self.disable(timeout)?;
+        }
+        let dbus_path = self.modem.dbus_path.clone();
+        let flag = format!("--set-power-state-{}", state);
+        run_fun!(
+            mmcli -m $dbus_path $flag
+        )
+        .map_err(io::Error::other)?;
+        if state == PowerState::Off {
+            // the modem may vanish from the bus once powered off
+            return Ok(());
+        }
+        let expected = state.to_string();
+        self.wait_state(timeout, |generic| generic.power_state == expected)
+    }
+
+    /// Soft reset, returns the modem once it has been probed again.
+    pub fn reset(&self, timeout: Duration) -> io::Result<ModemInfo> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --reset
+        )
+        .map_err(io::Error::other)?;
+        self.wait_reprobe(timeout, |modem_info| {
+            modem_info.modem.dbus_path != dbus_path
+        })
+    }
+
+    /// Reset to factory settings with the carrier service programming
+    /// code, returns the modem once it has been probed again.
+    pub fn factory_reset(&self, code: &str, timeout: Duration) -> io::Result<ModemInfo> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --factory-reset=$code
+        )
+        .map_err(io::Error::other)?;
+        self.wait_reprobe(timeout, |modem_info| {
+            modem_info.modem.dbus_path != dbus_path
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_state() {
+        let mut modem_info = ModemInfo::default();
+        modem_info.modem.generic.power_state = "low".to_string();
+        assert_eq!(modem_info.power_state(), Some(PowerState::Low));
+        assert!("unknown".parse::<PowerState>().is_err());
+        assert_eq!(PowerState::Off.to_string(), "off");
+    }
+}
@@ -0,0 +1,219 @@
+use std::{io, str::FromStr};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    bearer::{Auth, IpType},
+    mmcli,
+    modem::ModemInfo,
+};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub modem: ProfileModem,
+}
+
+impl FromStr for ProfileInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileModem {
+    #[serde(rename = "3gpp")]
+    pub n3gpp: Profile3gpp,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile3gpp {
+    #[serde(rename = "profile-manager")]
+    pub profile_manager: ProfileManager,
+}
+
+/// Entries are `key: value` strings in some mmcli releases and objects in
+/// others.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProfileManager {
+    pub list: Vec<Value>,
+}
+
+/// A connection profile stored in the modem.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Profile {
+    /// `None` creates a new profile
+    pub profile_id: Option<u32>,
+    pub profile_name: String,
+    pub apn: String,
+    pub apn_type: String,
+    pub ip_type: Option<IpType>,
+    pub user: String,
+    pub password: String,
+    pub allowed_auth: Vec<Auth>,
+}
+
+impl Profile {
+    fn from_pairs(pairs: Vec<(String, String)>) -> Self {
+        let mut profile = Profile::default();
+        for (key, value) in pairs {
+            let value = match value.as_str() {
+                "--" => String::new(),
+                _ => value,
+            };
+            match key.as_str() {
+                "profile-id" => profile.profile_id = value.parse().ok(),
+                "profile-name" => profile.profile_name = value,
+                "apn" => profile.apn = value,
+                "apn-type" => profile.apn_type = value,
+                "ip-type" => profile.ip_type = value.parse().ok(),
+                "user" => profile.user = value,
+                "password" => profile.password = value,
+                "allowed-auth" => {
+                    profile.allowed_auth = value
+                        .split([',', '|'])
+                        .filter_map(|auth| auth.trim().parse().ok())
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        profile
+    }
+
+    fn from_value(value: &Value) -> Self {
+        let pairs = match value {
+            Value::String(s) => mmcli::parse_key_values(s),
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        Value::Array(items) => items
+                            .iter()
+                            .filter_map(|i| i.as_str())
+                            .collect::<Vec<&str>>()
+                            .join("|"),
+                        value => value.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self::from_pairs(pairs)
+    }
+
+    /// The `key=value` list accepted by `--3gpp-profile-manager-set`.
+    pub fn to_args(&self) -> io::Result<String> {
+        let allowed_auth = self
+            .allowed_auth
+            .iter()
+            .map(|auth| auth.to_string())
+            .collect::<Vec<String>>()
+            .join("|");
+        mmcli::key_values(&[
+            (
+                "profile-id",
+                self.profile_id.map(|id| id.to_string()).unwrap_or_default(),
+            ),
+            ("profile-name", self.profile_name.clone()),
+            ("apn", self.apn.clone()),
+            ("apn-type", self.apn_type.clone()),
+            (
+                "ip-type",
+                self.ip_type.map(|t| t.to_string()).unwrap_or_default(),
+            ),
+            ("user", self.user.clone()),
+            ("password", self.password.clone()),
+            ("allowed-auth", allowed_auth),
+        ])
+    }
+}
+
+impl ModemInfo {
+    pub fn profiles(&self) -> io::Result<Vec<Profile>> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let info: ProfileInfo = run_fun!(
+            mmcli -m $dbus_path --3gpp-profile-manager-list -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        Ok(info
+            .modem
+            .n3gpp
+            .profile_manager
+            .list
+            .iter()
+            .map(Profile::from_value)
+            .collect())
+    }
+
+    pub fn profile(&self, profile_id: u32) -> io::Result<Profile> {
+        self.profiles()?
+            .into_iter()
+            .find(|p| p.profile_id == Some(profile_id))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("No profile {}", profile_id),
+                )
+            })
+    }
+
+    /// Create or update a profile, fields left empty keep the modem values.
+    pub fn set_profile(&self, profile: &Profile) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = profile.to_args()?;
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-profile-manager-set=$args
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn delete_profile(&self, profile_id: u32) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = mmcli::key_values(&[("profile-id", profile_id.to_string())])?;
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-profile-manager-delete=$args
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        let json = r#"{"modem":{"3gpp":{"profile-manager":{"list":["profile-id: 1, apn: cmnet, ip-type: ipv4v6, apn-type: default, allowed-auth: pap, chap","profile-id: 2, apn: ims, ip-type: ipv6, apn-type: ims, user: --"]}}}}"#;
+        let info: ProfileInfo = json.parse().unwrap();
+        let profiles = info
+            .modem
+            .n3gpp
+            .profile_manager
+            .list
+            .iter()
+            .map(Profile::from_value)
+            .collect::<Vec<Profile>>();
+        assert_eq!(profiles[0].profile_id, Some(1));
+        assert_eq!(profiles[0].allowed_auth, vec![Auth::Pap, Auth::Chap]);
+        assert_eq!(profiles[1].ip_type, Some(IpType::Ipv6));
+        assert_eq!(profiles[1].user, "");
+        assert_eq!(
+            profiles[1].to_args().unwrap(),
+            "profile-id='2',apn='ims',apn-type='ims',ip-type='ipv6'"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use std::{fmt::Display, io, str::FromStr};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::modem::ModemInfo;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalInfo {
+    pub modem: SignalModem,
+}
+
+impl FromStr for SignalInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalModem {
+    pub signal: RawSignal,
+}
+
+/// `--signal-get` output, older ModemManager releases omit some sections.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RawSignal {
+    #[serde(rename = "5g")]
+    pub n5g: RawMetrics,
+    pub cdma1x: RawMetrics,
+    pub evdo: RawMetrics,
+    pub gsm: RawMetrics,
+    pub lte: RawMetrics,
+    pub umts: RawMetrics,
+    pub refresh: Refresh,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RawMetrics {
+    pub ecio: String,
+    #[serde(rename = "error-rate")]
+    pub error_rate: String,
+    pub io: String,
+    pub rscp: String,
+    pub rsrp: String,
+    pub rsrq: String,
+    pub rssi: String,
+    pub sinr: String,
+    pub snr: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Refresh {
+    pub rate: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Grade {
+    Poor,
+    Fair,
+    Good,
+    Excellent,
+}
+
+impl Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grade::Excellent => write!(f, "excellent"),
+            Grade::Good => write!(f, "good"),
+            Grade::Fair => write!(f, "fair"),
+            Grade::Poor => write!(f, "poor"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricKind {
+    Rssi,
+    Rscp,
+    Ecio,
+    Rsrp,
+    Rsrq,
+    /// Signal to noise ratio, reported for LTE and 5G
+    Snr,
+    /// Signal to interference plus noise ratio, reported for EV-DO
+    Sinr,
+}
+
+impl Display for MetricKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricKind::Rssi => write!(f, "RSSI"),
+            MetricKind::Rscp => write!(f, "RSCP"),
+            MetricKind::Ecio => write!(f, "EcIo"),
+            MetricKind::Rsrp => write!(f, "RSRP"),
+            MetricKind::Rsrq => write!(f, "RSRQ"),
+            MetricKind::Snr => write!(f, "SNR"),
+            MetricKind::Sinr => write!(f, "SINR"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Technology {
+    Gsm,
+    Umts,
+    Lte,
+    Nr5g,
+    Cdma1x,
+    Evdo,
+}
+
+impl Display for Technology {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Technology::Gsm => write!(f, "GSM"),
+            Technology::Umts => write!(f, "UMTS"),
+            Technology::Lte => write!(f, "LTE"),
+            Technology::Nr5g => write!(f, "5G"),
+            Technology::Cdma1x => write!(f, "CDMA1x"),
+            Technology::Evdo => write!(f, "EV-DO"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metric {
+    pub kind: MetricKind,
+    pub value: f64,
+}
+
+impl Metric {
+    pub fn unit(&self) -> &'static str {
+        match self.kind {
+            MetricKind::Rssi | MetricKind::Rscp | MetricKind::Rsrp => "dBm",
+            _ => "dB",
+        }
+    }
+
+    /// Grade with the thresholds commonly used for antenna placement.
+    pub fn grade(&self, technology: Technology) -> Grade {
+        // lower bounds of excellent, good and fair
+        let (excellent, good, fair) = match (self.kind, technology) {
+            (MetricKind::Rssi, Technology::Lte) => (-65.0, -75.0, -85.0),
+            (MetricKind::Rssi, _) => (-70.0, -85.0, -100.0),
+            (MetricKind::Rscp, _) => (-80.0, -95.0, -105.0),
+            (MetricKind::Ecio, _) => (-6.0, -10.0, -20.0),
+            (MetricKind::Rsrp, _) => (-80.0, -90.0, -100.0),
+            (MetricKind::Rsrq, _) => (-10.0, -15.0, -20.0),
+            (MetricKind::Snr, _) => (20.0, 13.0, 0.0),
+            (MetricKind::Sinr, Technology::Evdo) => (9.0, 3.0, -3.0),
+            (MetricKind::Sinr, _) => (20.0, 13.0, 0.0),
+        };
+        match self.value {
+            v if v >= excellent => Grade::Excellent,
+            v if v >= good => Grade::Good,
+            v if v >= fair => Grade::Fair,
+            _ => Grade::Poor,
+        }
+    }
+}
+
+/// Metrics of one access technology.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TechnologySignal {
+    pub technology: Technology,
+    pub metrics: Vec<Metric>,
+}
+
+impl TechnologySignal {
+    /// The worst grade of the metrics, `None` if nothing was reported.
+    pub fn grade(&self) -> Option<Grade> {
+        self.metrics
+            .iter()
+            .map(|m| m.grade(self.technology))
+            .min_by(|a, b| a.partial_cmp(b).unwrap())
+    }
+
+    pub fn metric(&self, kind: MetricKind) -> Option<f64> {
+        self.metrics
+            .iter()
+            .find(|m| m.kind == kind)
+            .map(|m| m.value)
+    }
+}
+
+/// Typed extended signal information, only technologies with at least one
+/// reported metric are included.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Signal {
+    pub technologies: Vec<TechnologySignal>,
+    /// Refresh rate in seconds, 0 when extended signal is not set up
+    pub refresh_rate: u32,
+}
+
+impl Signal {
+    pub fn get(&self, technology: Technology) -> Option<&TechnologySignal> {
+        self.technologies
+            .iter()
+            .find(|t| t.technology == technology)
+    }
+}
+
+fn metrics(raw: &RawMetrics, kinds: &[MetricKind]) -> Vec<Metric> {
+    kinds
+        .iter()
+        .filter_map(|kind| {
+            let value = match kind {
+                MetricKind::Rssi => &raw.rssi,
+                MetricKind::Rscp => &raw.rscp,
+                MetricKind::Ecio => &raw.ecio,
+                MetricKind::Rsrp => &raw.rsrp,
+                MetricKind::Rsrq => &raw.rsrq,
+                MetricKind::Snr => &raw.snr,
+                MetricKind::Sinr => &raw.sinr,
+            };
+            value
+                .parse()
+                .ok()
+                .map(|value| Metric { kind: *kind, value })
+        })
+        .collect()
+}
+
+impl From<&RawSignal> for Signal {
+    fn from(raw: &RawSignal) -> Self {
+        use MetricKind::*;
+        let sections = [
+            (Technology::Gsm, &raw.gsm, vec![Rssi]),
+            (Technology::Umts, &raw.umts, vec![Rssi, Rscp, Ecio]),
+            (Technology::Lte, &raw.lte, vec![Rssi, Rsrp, Rsrq, Snr, Sinr]),
+            (Technology::Nr5g, &raw.n5g, vec![Rsrp, Rsrq, Snr, Sinr]),
+            (Technology::Cdma1x, &raw.cdma1x, vec![Rssi, Ecio]),
+            (Technology::Evdo, &raw.evdo, vec![Rssi, Ecio, Sinr]),
+        ];
+        let technologies = sections
+            .into_iter()
+            .map(|(technology, raw, kinds)| TechnologySignal {
+                technology,
+                metrics: metrics(raw, &kinds),
+            })
+            .filter(|t| !t.metrics.is_empty())
+            .collect();
+        Signal {
+            technologies,
+            refresh_rate: raw.refresh.rate.parse().unwrap_or(0),
+        }
+    }
+}
+
+impl ModemInfo {
+    /// Poll extended signal information every `rate` seconds, 0 disables it.
+    pub fn signal_setup(&self, rate: u32) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --signal-setup=$rate
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn signal(&self) -> io::Result<Signal> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let info: SignalInfo = run_fun!(
+            mmcli -m $dbus_path --signal-get -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        Ok(Signal::from(&info.modem.signal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal() {
+        let json = r#"{"modem":{"signal":{"5g":{"error-rate":"--","rsrp":"--","rsrq":"--","snr":"--"},"gsm":{"error-rate":"--","rssi":"--"},"lte":{"error-rate":"--","rsrp":"-95.00","rsrq":"-11.00","rssi":"-70.00","snr":"21.40"},"refresh":{"rate":"5"},"umts":{"ecio":"--","error-rate":"--","rscp":"--","rssi":"--"}}}}"#;
+        let info: SignalInfo = json.parse().unwrap();
+        let signal = Signal::from(&info.modem.signal);
+        assert_eq!(signal.refresh_rate, 5);
+        assert_eq!(signal.technologies.len(), 1);
+        let lte = signal.get(Technology::Lte).unwrap();
+        assert_eq!(lte.metric(MetricKind::Rsrp), Some(-95.0));
+        assert_eq!(lte.metric(MetricKind::Snr), Some(21.4));
+        assert_eq!(lte.metric(MetricKind::Sinr), None);
+        assert_eq!(lte.grade(), Some(Grade::Fair));
+    }
+}
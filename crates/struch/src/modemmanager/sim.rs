@@ -0,0 +1,295 @@
+use std::{fmt::Display, io, str::FromStr, thread, time::Duration};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::modem::ModemInfo;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimInfo {
+    pub sim: Sim,
+}
+
+impl FromStr for SimInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+impl Display for SimInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).unwrap())
+    }
+}
+
+impl SimInfo {
+    pub fn new(sim_id: impl Display) -> io::Result<SimInfo> {
+        run_fun!(
+            mmcli -i $sim_id -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)
+    }
+
+    pub fn refresh(&mut self) -> io::Result<()> {
+        let dbus_path = self.sim.dbus_path.clone();
+        self.sim = Self::new(dbus_path)?.sim;
+        Ok(())
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.sim.properties.active == "yes"
+    }
+
+    pub fn send_pin(&self, pin: &str) -> io::Result<()> {
+        let dbus_path = self.sim.dbus_path.clone();
+        run_fun!(
+            mmcli -i $dbus_path --pin=$pin
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Unblock the SIM with the PUK code and set `new_pin`.
+    pub fn send_puk(&self, puk: &str, new_pin: &str) -> io::Result<()> {
+        let dbus_path = self.sim.dbus_path.clone();
+        run_fun!(
+            mmcli -i $dbus_path --puk=$puk --pin=$new_pin
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn change_pin(&self, old_pin: &str, new_pin: &str) -> io::Result<()> {
+        let dbus_path = self.sim.dbus_path.clone();
+        run_fun!(
+            mmcli -i $dbus_path --pin=$old_pin --change-pin=$new_pin
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Require the PIN on every power up.
+    pub fn enable_pin(&self, pin: &str) -> io::Result<()> {
+        let dbus_path = self.sim.dbus_path.clone();
+        run_fun!(
+            mmcli -i $dbus_path --pin=$pin --enable-pin
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    pub fn disable_pin(&self, pin: &str) -> io::Result<()> {
+        let dbus_path = self.sim.dbus_path.clone();
+        run_fun!(
+            mmcli -i $dbus_path --pin=$pin --disable-pin
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sim {
+    #[serde(rename = "dbus-path")]
+    pub dbus_path: String,
+    pub properties: SimProperties,
+}
+
+/// Older ModemManager releases omit the eSIM and emergency number fields.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SimProperties {
+    pub active: String,
+    pub eid: String,
+    #[serde(rename = "emergency-numbers")]
+    pub emergency_numbers: Vec<String>,
+    #[serde(rename = "esim-status")]
+    pub esim_status: String,
+    pub gid1: String,
+    pub gid2: String,
+    pub iccid: String,
+    pub imsi: String,
+    #[serde(rename = "operator-code")]
+    pub operator_code: String,
+    #[serde(rename = "operator-name")]
+    pub operator_name: String,
+    pub removability: String,
+    #[serde(rename = "sim-type")]
+    pub sim_type: String,
+}
+
+/// Remaining attempts of one lock, parsed from `Generic.unlock_retries`
+/// entries such as `sim-pin (3)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnlockRetries {
+    pub lock: String,
+    pub retries: u32,
+}
+
+impl FromStr for UnlockRetries {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lock, retries) = s
+            .split_once('(')
+            .ok_or_else(|| format!("Invalid unlock retries: {}", s))?;
+        let retries = retries
+            .trim_end_matches(')')
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid unlock retries: {}", s))?;
+        Ok(UnlockRetries {
+            lock: lock.trim().to_string(),
+            retries,
+        })
+    }
+}
+
+/// One SIM slot of a multi-SIM modem, slots are numbered from 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimSlot {
+    pub slot: u32,
+    pub sim: Option<SimInfo>,
+    pub primary: bool,
+}
+
+impl ModemInfo {
+    /// The SIM in the primary slot, `None` if there is no SIM.
+    pub fn sim(&self) -> io::Result<Option<SimInfo>> {
+        match self.modem.generic.sim.as_str() {
+            "" | "--" | "/" => Ok(None),
+            sim => SimInfo::new(sim).map(Some),
+        }
+    }
+
+    pub fn unlock_retries(&self) -> Vec<UnlockRetries> {
+        self.modem
+            .generic
+            .unlock_retries
+            .iter()
+            .filter_map(|r| r.parse().ok())
+            .collect()
+    }
+
+    pub fn retries_for(&self, lock: &str) -> Option<u32> {
+        self.unlock_retries()
+            .into_iter()
+            .find(|r| r.lock == lock)
+            .map(|r| r.retries)
+    }
+
+    /// Unlock a modem waiting for the SIM PIN and wait until it leaves the
+    /// locked state.
+    ///
+    /// Refuses to try when a single attempt is left, a wrong PIN from a
+    /// stale configuration would otherwise block the SIM.
+    /// Any other lock, such as `sim-puk`, is an error naming the lock and
+    /// its remaining attempts.
+    pub fn unlock(&mut self, pin: &str) -> io::Result<()> {
+        match self.modem.generic.unlock_required.as_str() {
+            "" | "--" | "none" => return Ok(()),
+            "sim-pin" => {}
+            lock => {
+                let retries = self
+                    .retries_for(lock)
+                    .map(|retries| format!("{} attempts left", retries))
+                    .unwrap_or_else(|| "attempts unknown".to_string());
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "Modem requires {} ({}), a PIN can't unlock it",
+                        lock, retries
+                    ),
+                ));
+            }
+        }
+        if let Some(retries) = self.retries_for("sim-pin") {
+            if retries <= 1 {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("Only {} PIN attempt left, unlock the SIM manually", retries),
+                ));
+            }
+        }
+        let sim = self
+            .sim()?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No SIM found"))?;
+        sim.send_pin(pin)?;
+        for _ in 0..30 {
+            self.refresh()?;
+            if self.modem.generic.state != "locked" {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_secs(1));
+        }
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "Modem still locked after sending the PIN",
+        ))
+    }
+
+    pub fn sim_slots(&self) -> io::Result<Vec<SimSlot>> {
+        let primary = self.modem.generic.primary_sim_slot.parse::<u32>().ok();
+        let mut slots = Vec::new();
+        for (index, path) in self.modem.generic.sim_slots.iter().enumerate() {
+            let slot = index as u32 + 1;
+            let sim = match path.as_str() {
+                "" | "--" | "/" => None,
+                path => Some(SimInfo::new(path)?),
+            };
+            slots.push(SimSlot {
+                slot,
+                sim,
+                primary: primary == Some(slot),
+            });
+        }
+        Ok(slots)
+    }
+
+    /// Switch the primary SIM slot.
+    ///
+    /// The modem is re-probed by ModemManager and comes back under a new
+    /// index, so this waits up to `timeout` for it to reappear and returns
+    /// the new `ModemInfo`, matched by its physical device path.
+    pub fn set_primary_sim_slot(&self, slot: u32, timeout: Duration) -> io::Result<ModemInfo> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --set-primary-sim-slot=$slot
+        )
+        .map_err(io::Error::other)?;
+        self.wait_reprobe(timeout, |modem_info| {
+            modem_info.modem.dbus_path != dbus_path
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_retries() {
+        let retries: UnlockRetries = "sim-pin (3)".parse().unwrap();
+        assert_eq!(retries.lock, "sim-pin");
+        assert_eq!(retries.retries, 3);
+        assert!("sim-pin".parse::<UnlockRetries>().is_err());
+    }
+
+    #[test]
+    fn test_unlock_other_lock() {
+        let mut modem_info = ModemInfo::default();
+        modem_info.modem.generic.unlock_required = "--".to_string();
+        assert!(modem_info.unlock("1234").is_ok());
+        modem_info.modem.generic.unlock_required = "sim-puk".to_string();
+        modem_info.modem.generic.unlock_retries = vec!["sim-puk (10)".to_string()];
+        let err = modem_info.unlock("1234").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("sim-puk (10 attempts left)"));
+    }
+}
@@ -0,0 +1,154 @@
+use std::{fmt::Display, io, str::FromStr};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    mmcli::{created_path, key_values},
+    modem::ModemInfo,
+};
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmsList {
+    #[serde(rename = "modem.messaging.sms")]
+    pub sms_list: Vec<String>,
+}
+
+impl FromStr for SmsList {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmsInfo {
+    pub sms: Sms,
+}
+
+impl FromStr for SmsInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+impl Display for SmsInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).unwrap())
+    }
+}
+
+impl SmsInfo {
+    pub fn new(sms_id: impl Display) -> io::Result<SmsInfo> {
+        run_fun!(
+            mmcli -s $sms_id -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)
+    }
+
+    pub fn refresh(&mut self) -> io::Result<()> {
+        let dbus_path = self.sms.dbus_path.clone();
+        self.sms = Self::new(dbus_path)?.sms;
+        Ok(())
+    }
+
+    pub fn send(&self) -> io::Result<()> {
+        let dbus_path = self.sms.dbus_path.clone();
+        run_fun!(
+            mmcli -s $dbus_path --send
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+
+    /// Store the message, `storage` is one of `sm` (SIM), `me`, `mt`, `sr`,
+    /// `bm` or `ta`.
+    pub fn store(&self, storage: &str) -> io::Result<()> {
+        let dbus_path = self.sms.dbus_path.clone();
+        run_fun!(
+            mmcli -s $dbus_path --store-in-storage=$storage
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+impl ModemInfo {
+    pub fn list_sms(&self) -> io::Result<Vec<SmsInfo>> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let sms_list: SmsList = run_fun!(
+            mmcli -m $dbus_path --messaging-list-sms -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        sms_list.sms_list.iter().map(SmsInfo::new).collect()
+    }
+
+    /// Create a text message in the modem, it still has to be sent or stored.
+    pub fn create_sms(&self, number: &str, text: &str) -> io::Result<SmsInfo> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let args = key_values(&[("number", number.to_string()), ("text", text.to_string())])?;
+        let reply = run_fun!(
+            mmcli -m $dbus_path --messaging-create-sms=$args
+        )
+        .map_err(io::Error::other)?;
+        SmsInfo::new(created_path(&reply)?)
+    }
+
+    pub fn delete_sms(&self, sms_id: impl Display) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let sms_id = sms_id.to_string();
+        run_fun!(
+            mmcli -m $dbus_path --messaging-delete-sms=$sms_id
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sms {
+    pub content: Content,
+    #[serde(rename = "dbus-path")]
+    pub dbus_path: String,
+    pub properties: SmsProperties,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Content {
+    pub data: String,
+    pub number: String,
+    pub text: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmsProperties {
+    pub class: String,
+    #[serde(rename = "delivery-report")]
+    pub delivery_report: String,
+    #[serde(rename = "delivery-state")]
+    pub delivery_state: String,
+    #[serde(rename = "discharge-timestamp")]
+    pub discharge_timestamp: String,
+    #[serde(rename = "message-reference")]
+    pub message_reference: String,
+    #[serde(rename = "pdu-type")]
+    pub pdu_type: String,
+    pub smsc: String,
+    /// unknown, stored, receiving, received, sending or sent
+    pub state: String,
+    pub storage: String,
+    pub timestamp: String,
+    pub validity: String,
+}
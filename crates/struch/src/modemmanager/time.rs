@@ -0,0 +1,168 @@
+use std::{
+    io,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::modem::ModemInfo;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeInfo {
+    pub modem: TimeModem,
+}
+
+impl FromStr for TimeInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeModem {
+    pub time: RawTime,
+}
+
+/// `--time` output, the timezone is missing until the network sent NITZ.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RawTime {
+    pub current: String,
+    #[serde(rename = "network-timezone")]
+    pub network_timezone: NetworkTimezone,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkTimezone {
+    #[serde(rename = "dst-offset")]
+    pub dst_offset: String,
+    #[serde(rename = "leap-seconds")]
+    pub leap_seconds: String,
+    /// Minutes east of UTC
+    pub offset: String,
+}
+
+/// Network time as reported by the modem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkTime {
+    /// Seconds since the epoch, `None` without a UTC offset
+    pub unix: Option<i64>,
+    /// Offset from UTC in minutes, including daylight saving
+    pub utc_offset: Option<i32>,
+    pub dst_offset: Option<i32>,
+    pub leap_seconds: Option<i32>,
+    /// Time as printed by the modem, e.g. `2024-05-10T08:15:30+08:00`
+    pub local: String,
+}
+
+impl NetworkTime {
+    /// Seconds the system clock is ahead of the network time, `None` when
+    /// the UTC offset is unknown.
+    pub fn drift(&self) -> Option<i64> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        self.unix.map(|unix| now - unix)
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = (month + 9) % 12;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Parse `YYYY-MM-DDTHH:MM:SS[+-HH:MM|Z]`, returning seconds since the
+/// epoch and the offset in minutes when present.
+fn parse_iso8601(s: &str) -> Option<(i64, Option<i32>)> {
+    let (date, time) = s.trim().split_once('T')?;
+    let mut date = date.split('-').map(|v| v.parse::<i64>());
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let (clock, offset) = match time.find(['+', '-', 'Z']) {
+        Some(index) => (&time[..index], Some(&time[index..])),
+        None => (time, None),
+    };
+    let mut clock = clock.split(':').map(|v| v.parse::<f64>());
+    let hour = clock.next()?.ok()? as i64;
+    let minute = clock.next()?.ok()? as i64;
+    let second = clock.next().unwrap_or(Ok(0.0)).ok()? as i64;
+    let offset = match offset {
+        None => None,
+        Some("Z") => Some(0),
+        Some(offset) => {
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let (hours, minutes) = offset[1..].split_once(':').unwrap_or((&offset[1..], "0"));
+            Some(sign * (hours.parse::<i32>().ok()? * 60 + minutes.parse::<i32>().ok()?))
+        }
+    };
+    let local = days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+    Some((local, offset))
+}
+
+impl TryFrom<&RawTime> for NetworkTime {
+    type Error = String;
+
+    fn try_from(raw: &RawTime) -> Result<Self, Self::Error> {
+        let (local, offset) =
+            parse_iso8601(&raw.current).ok_or_else(|| format!("Invalid time: {}", raw.current))?;
+        let number = |value: &str| value.parse::<i32>().ok();
+        let timezone = &raw.network_timezone;
+        let utc_offset = offset.or(number(&timezone.offset));
+        Ok(NetworkTime {
+            unix: utc_offset.map(|offset| local - offset as i64 * 60),
+            utc_offset,
+            dst_offset: number(&timezone.dst_offset),
+            leap_seconds: number(&timezone.leap_seconds),
+            local: raw.current.clone(),
+        })
+    }
+}
+
+impl ModemInfo {
+    pub fn network_time(&self) -> io::Result<NetworkTime> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let info: TimeInfo = run_fun!(
+            mmcli -m $dbus_path --time -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        NetworkTime::try_from(&info.modem.time).map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_time() {
+        let json = r#"{"modem":{"time":{"current":"2024-05-10T08:15:30+08:00","network-timezone":{"dst-offset":"0","leap-seconds":"--","offset":"480"}}}}"#;
+        let info: TimeInfo = json.parse().unwrap();
+        let time = NetworkTime::try_from(&info.modem.time).unwrap();
+        assert_eq!(time.unix, Some(1715300130));
+        assert_eq!(time.utc_offset, Some(480));
+        assert_eq!(time.dst_offset, Some(0));
+        assert_eq!(time.leap_seconds, None);
+        let raw = RawTime {
+            current: "2024-05-10T08:15:30".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(NetworkTime::try_from(&raw).unwrap().unix, None);
+        assert_eq!(parse_iso8601("1970-01-01T00:00:00Z"), Some((0, Some(0))));
+        assert_eq!(parse_iso8601("1969-12-31T23:00:00-01:00").unwrap().0, -3600);
+    }
+}
@@ -0,0 +1,286 @@
+use std::{fmt::Display, io, str::FromStr};
+
+use cmd_lib::run_fun;
+use serde_derive::{Deserialize, Serialize};
+
+use super::modem::ModemInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UssdState {
+    Idle,
+    Active,
+    /// The network asked a question, answer with `ussd_respond`
+    UserResponse,
+    Unknown,
+}
+
+impl FromStr for UssdState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "idle" => Ok(UssdState::Idle),
+            "active" => Ok(UssdState::Active),
+            "user-response" => Ok(UssdState::UserResponse),
+            _ => Ok(UssdState::Unknown),
+        }
+    }
+}
+
+impl Display for UssdState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UssdState::Idle => write!(f, "idle"),
+            UssdState::Active => write!(f, "active"),
+            UssdState::UserResponse => write!(f, "user-response"),
+            UssdState::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UssdInfo {
+    pub modem: UssdModem,
+}
+
+impl FromStr for UssdInfo {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UssdModem {
+    #[serde(rename = "3gpp")]
+    pub n3gpp: Ussd3gpp,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ussd3gpp {
+    pub ussd: Ussd,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ussd {
+    #[serde(rename = "network-notification")]
+    pub network_notification: String,
+    #[serde(rename = "network-request")]
+    pub network_request: String,
+    pub status: String,
+}
+
+impl Ussd {
+    pub fn state(&self) -> UssdState {
+        UssdState::from_str(&self.status).unwrap_or(UssdState::Unknown)
+    }
+}
+
+/// The quoted network reply of `--3gpp-ussd-initiate` and `--3gpp-ussd-respond`.
+fn network_reply(reply: &str) -> String {
+    match (reply.find('\''), reply.rfind('\'')) {
+        (Some(start), Some(end)) if start < end => reply[start + 1..end].to_string(),
+        _ => reply.trim().to_string(),
+    }
+}
+
+impl ModemInfo {
+    pub fn ussd_status(&self) -> io::Result<Ussd> {
+        let dbus_path = self.modem.dbus_path.clone();
+        let info: UssdInfo = run_fun!(
+            mmcli -m $dbus_path --3gpp-ussd-status -J
+        )
+        .map_err(io::Error::other)?
+        .parse()
+        .map_err(io::Error::other)?;
+        Ok(info.modem.n3gpp.ussd)
+    }
+
+    /// Start a session with `code` such as `*100#` and return the reply.
+    pub fn ussd_initiate(&self, code: &str) -> io::Result<String> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-ussd-initiate=$code
+        )
+        .map_err(io::Error::other)
+        .map(|reply| network_reply(&reply))
+    }
+
+    /// Answer a network menu while the session is in `UserResponse`.
+    pub fn ussd_respond(&self, response: &str) -> io::Result<String> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-ussd-respond=$response
+        )
+        .map_err(io::Error::other)
+        .map(|reply| network_reply(&reply))
+    }
+
+    pub fn ussd_cancel(&self) -> io::Result<()> {
+        let dbus_path = self.modem.dbus_path.clone();
+        run_fun!(
+            mmcli -m $dbus_path --3gpp-ussd-cancel
+        )
+        .map_err(io::Error::other)
+        .map(|_| ())
+    }
+}
+
+/// Balance and data allowance found in a carrier USSD reply.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub amount: Option<f64>,
+    pub currency: Option<String>,
+    /// Remaining data in MB
+    pub data_mb: Option<f64>,
+}
+
+static BALANCE_KEYWORDS: &[&str] = &["balance", "bal", "credit", "余额", "话费"];
+static CURRENCIES: &[&str] = &[
+    "$", "€", "£", "¥", "₹", "₦", "元", "usd", "eur", "gbp", "cny", "rmb", "inr", "kes", "ngn",
+    "zar", "php", "idr", "rs", "yuan",
+];
+
+/// A number in the text and the byte offsets around it.
+struct Number {
+    value: f64,
+    start: usize,
+    end: usize,
+}
+
+fn numbers(text: &str) -> Vec<Number> {
+    let mut numbers = Vec::new();
+    let mut start = None;
+    let chars = text.char_indices().chain([(text.len(), ' ')]);
+    for (i, c) in chars {
+        let part_of_number = c.is_ascii_digit()
+            || (start.is_some() && (c == '.' || c == ',') && {
+                let rest = &text[i + c.len_utf8()..];
+                rest.starts_with(|n: char| n.is_ascii_digit())
+            });
+        match (start, part_of_number) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                let raw = text[s..i].replace(',', "");
+                if let Ok(value) = raw.parse() {
+                    numbers.push(Number {
+                        value,
+                        start: s,
+                        end: i,
+                    });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    numbers
+}
+
+/// Size of the data unit following a number, in MB.
+fn data_unit(rest: &str) -> Option<f64> {
+    let unit = rest
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect::<String>()
+        .to_lowercase();
+    match unit.as_str() {
+        "gb" | "g" => Some(1024.0),
+        "mb" | "m" => Some(1.0),
+        "kb" | "k" => Some(1.0 / 1024.0),
+        _ => None,
+    }
+}
+
+fn currency_near(before: &str, after: &str) -> Option<String> {
+    let before = before.trim_end().to_lowercase();
+    let after = after.trim_start().to_lowercase();
+    let is_letter = |c: Option<char>| c.map(|c| c.is_ascii_alphabetic()).unwrap_or(false);
+    CURRENCIES
+        .iter()
+        .find(|c| {
+            // codes like `rs` must not be the tail or head of another word
+            let code = c.starts_with(|c: char| c.is_ascii_alphabetic());
+            let prefix = before
+                .strip_suffix(*c)
+                .map(|rest| !code || !is_letter(rest.chars().last()))
+                .unwrap_or(false);
+            let suffix = after
+                .strip_prefix(*c)
+                .map(|rest| !code || !is_letter(rest.chars().next()))
+                .unwrap_or(false);
+            prefix || suffix
+        })
+        .map(|c| c.to_uppercase())
+}
+
+impl Balance {
+    /// Best effort extraction from free-form replies such as
+    /// `Your balance is $12.50. Data: 1.5GB remaining` or `余额: 23.40元`.
+    pub fn parse(text: &str) -> Balance {
+        let lower = text.to_lowercase();
+        let mut balance = Balance::default();
+        let keyword = BALANCE_KEYWORDS
+            .iter()
+            .filter_map(|k| lower.find(k).map(|i| i + k.len()))
+            .min();
+        for number in numbers(&lower) {
+            let (before, after) = (&lower[..number.start], &lower[number.end..]);
+            if let Some(unit) = data_unit(after) {
+                if balance.data_mb.is_none() {
+                    balance.data_mb = Some(number.value * unit);
+                }
+                continue;
+            }
+            if balance.amount.is_some() {
+                continue;
+            }
+            let currency = currency_near(before, after);
+            let after_keyword = keyword.map(|k| number.start >= k).unwrap_or(false);
+            if currency.is_some() || after_keyword {
+                balance.amount = Some(number.value);
+                balance.currency = currency;
+            }
+        }
+        balance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balance() {
+        let balance =
+            Balance::parse("Your balance is $12.50. Data: 1.5GB remaining until 2024-07-01");
+        assert_eq!(balance.amount, Some(12.5));
+        assert_eq!(balance.currency.as_deref(), Some("$"));
+        assert_eq!(balance.data_mb, Some(1536.0));
+
+        let balance = Balance::parse("尊敬的客户，您的余额为23.40元，剩余流量512MB");
+        assert_eq!(balance.amount, Some(23.4));
+        assert_eq!(balance.currency.as_deref(), Some("元"));
+        assert_eq!(balance.data_mb, Some(512.0));
+
+        let balance = Balance::parse("Bal: KES 1,250.00. Bundles: 300 MB valid 3 days");
+        assert_eq!(balance.amount, Some(1250.0));
+        assert_eq!(balance.currency.as_deref(), Some("KES"));
+        assert_eq!(balance.data_mb, Some(300.0));
+
+        assert_eq!(Balance::parse("1. Buy bundle 2. Exit"), Balance::default());
+        assert_eq!(Balance::parse("Valid for 3 hours 20 minutes").amount, None);
+    }
+
+    #[test]
+    fn test_network_reply() {
+        let reply = "USSD session initiated; new reply from network: 'Balance: 5.00'";
+        assert_eq!(network_reply(reply), "Balance: 5.00");
+    }
+}
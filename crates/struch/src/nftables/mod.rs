@@ -0,0 +1,4 @@
+mod share;
+mod table;
+pub use share::Share;
+pub use table::{Chain, ChainType, CtState, Family, Hook, Match, Policy, Rule, Statement, Table};
@@ -0,0 +1,149 @@
+use std::{fs, io, path::Path};
+
+use super::table::{Chain, ChainType, CtState, Family, Hook, Match, Rule, Statement, Table};
+
+static IP_FORWARD: &str = "/proc/sys/net/ipv4/ip_forward";
+static STATE_DIR: &str = "/run/netool";
+static TABLE_PREFIX: &str = "netool_share_";
+
+/// Share the uplink `wan` with the `lan` interfaces through NAT.
+///
+/// Every share owns a dedicated nftables table, so stopping it removes
+/// exactly the rules it installed and nothing else.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Share {
+    pub lan: Vec<String>,
+    pub wan: String,
+    pub mtu: Option<u32>,
+}
+
+impl Share {
+    pub fn new(lan: &[String], wan: &str) -> Self {
+        Share {
+            lan: lan.to_vec(),
+            wan: wan.to_string(),
+            mtu: None,
+        }
+    }
+
+    pub fn mtu(mut self, mtu: u32) -> Self {
+        self.mtu = Some(mtu);
+        self
+    }
+
+    pub fn table_name(wan: &str) -> String {
+        format!("{}{}", TABLE_PREFIX, wan.replace(['-', '.'], "_"))
+    }
+
+    pub fn table(&self) -> Table {
+        let mut forward = Chain::new("forward", ChainType::Filter, Hook::Forward, 0);
+        if let Some(mtu) = self.mtu {
+            // 40 bytes of IPv4 and TCP headers
+            let mss = mtu.saturating_sub(40);
+            for (iif, oif) in [(None, Some(&self.wan)), (Some(&self.wan), None)] {
+                let mut rule = Rule::new(Statement::SetMss(mss));
+                if let Some(iif) = iif {
+                    rule = rule.with(Match::Iifname(iif.clone()));
+                }
+                if let Some(oif) = oif {
+                    rule = rule.with(Match::Oifname(oif.clone()));
+                }
+                forward = forward.rule(rule.with(Match::TcpSyn));
+            }
+        }
+        for lan in &self.lan {
+            forward = forward
+                .rule(
+                    Rule::new(Statement::Accept)
+                        .with(Match::Iifname(lan.clone()))
+                        .with(Match::Oifname(self.wan.clone())),
+                )
+                .rule(
+                    Rule::new(Statement::Accept)
+                        .with(Match::Iifname(self.wan.clone()))
+                        .with(Match::Oifname(lan.clone()))
+                        .with(Match::CtState(vec![CtState::Established, CtState::Related])),
+                );
+        }
+        let mut postrouting = Chain::new("postrouting", ChainType::Nat, Hook::Postrouting, 100);
+        for lan in &self.lan {
+            postrouting = postrouting.rule(
+                Rule::new(Statement::Masquerade)
+                    .with(Match::Iifname(lan.clone()))
+                    .with(Match::Oifname(self.wan.clone())),
+            );
+        }
+        Table::new(Family::Ip, &Self::table_name(&self.wan))
+            .chain(forward)
+            .chain(postrouting)
+    }
+
+    pub fn start(&self) -> io::Result<()> {
+        enable_forwarding()?;
+        self.table().apply()
+    }
+
+    pub fn is_active(wan: &str) -> bool {
+        Table::new(Family::Ip, &Self::table_name(wan)).exists()
+    }
+
+    /// Remove the rules of the share on `wan` and restore forwarding once
+    /// the last share is gone.
+    pub fn stop(wan: &str) -> io::Result<()> {
+        let table = Table::new(Family::Ip, &Self::table_name(wan));
+        if table.exists() {
+            table.delete()?;
+        }
+        let remaining = Table::list(Family::Ip)?
+            .iter()
+            .any(|name| name.starts_with(TABLE_PREFIX));
+        if !remaining {
+            restore_forwarding()?;
+        }
+        Ok(())
+    }
+}
+
+fn forward_state_file() -> String {
+    format!("{}/ip_forward", STATE_DIR)
+}
+
+/// Enable IPv4 forwarding, remembering the value it had before the first share.
+fn enable_forwarding() -> io::Result<()> {
+    let state = forward_state_file();
+    if !Path::new(&state).exists() {
+        let previous = fs::read_to_string(IP_FORWARD)?;
+        fs::create_dir_all(STATE_DIR)?;
+        fs::write(&state, previous.trim())?;
+    }
+    fs::write(IP_FORWARD, "1")
+}
+
+fn restore_forwarding() -> io::Result<()> {
+    let state = forward_state_file();
+    if let Ok(previous) = fs::read_to_string(&state) {
+        fs::write(IP_FORWARD, previous.trim())?;
+        fs::remove_file(&state)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_table() {
+        let share = Share::new(&["eth0".to_string()], "wwan0").mtu(1500);
+        let table = share.table().to_string();
+        println!("{}", table);
+        assert!(table.starts_with("table ip netool_share_wwan0 {"));
+        assert!(table
+            .contains("oifname \"wwan0\" tcp flags syn / syn,rst tcp option maxseg size set 1460"));
+        assert!(table.contains("iifname \"eth0\" oifname \"wwan0\" accept"));
+        assert!(table.contains(
+            "iifname \"wwan0\" oifname \"eth0\" ct state { established, related } accept"
+        ));
+        assert!(table.contains("iifname \"eth0\" oifname \"wwan0\" masquerade"));
+    }
+}
@@ -0,0 +1,285 @@
+use std::{fmt::Display, io};
+
+use cmd_lib::{run_cmd, run_fun};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Family {
+    Ip,
+    Ip6,
+    Inet,
+}
+
+impl Display for Family {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Family::Ip => write!(f, "ip"),
+            Family::Ip6 => write!(f, "ip6"),
+            Family::Inet => write!(f, "inet"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub family: Family,
+    pub name: String,
+    pub chains: Vec<Chain>,
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "table {} {} {{", self.family, self.name)?;
+        for chain in &self.chains {
+            write!(f, "{}", chain)?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl Table {
+    pub fn new(family: Family, name: &str) -> Self {
+        Table {
+            family,
+            name: name.to_string(),
+            chains: Vec::new(),
+        }
+    }
+
+    pub fn chain(mut self, chain: Chain) -> Self {
+        self.chains.push(chain);
+        self
+    }
+
+    /// Atomically replace the table in the kernel ruleset.
+    ///
+    /// The leading empty declaration makes the delete succeed when the table
+    /// does not exist yet, so applying twice never duplicates rules.
+    pub fn apply(&self) -> io::Result<()> {
+        let script = format!(
+            "table {family} {name}\ndelete table {family} {name}\n{table}",
+            family = self.family,
+            name = self.name,
+            table = self
+        );
+        run_cmd!(
+            echo $script | nft -f -
+        )
+    }
+
+    pub fn delete(&self) -> io::Result<()> {
+        let (family, name) = (self.family.to_string(), self.name.clone());
+        run_cmd!(
+            nft delete table $family $name
+        )
+    }
+
+    pub fn exists(&self) -> bool {
+        let (family, name) = (self.family.to_string(), self.name.clone());
+        run_fun!(
+            nft list table $family $name
+        )
+        .is_ok()
+    }
+
+    /// Names of all tables of `family` currently loaded in the kernel.
+    pub fn list(family: Family) -> io::Result<Vec<String>> {
+        let family = family.to_string();
+        let tables = run_fun!(
+            nft list tables $family
+        )?;
+        Ok(tables
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(2))
+            .map(|name| name.to_string())
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChainType {
+    Filter,
+    Nat,
+    Route,
+}
+
+impl Display for ChainType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChainType::Filter => write!(f, "filter"),
+            ChainType::Nat => write!(f, "nat"),
+            ChainType::Route => write!(f, "route"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hook {
+    Prerouting,
+    Input,
+    Forward,
+    Output,
+    Postrouting,
+}
+
+impl Display for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hook::Prerouting => write!(f, "prerouting"),
+            Hook::Input => write!(f, "input"),
+            Hook::Forward => write!(f, "forward"),
+            Hook::Output => write!(f, "output"),
+            Hook::Postrouting => write!(f, "postrouting"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Policy {
+    Accept,
+    Drop,
+}
+
+impl Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Policy::Accept => write!(f, "accept"),
+            Policy::Drop => write!(f, "drop"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub name: String,
+    pub chain_type: ChainType,
+    pub hook: Hook,
+    pub priority: i32,
+    pub policy: Policy,
+    pub rules: Vec<Rule>,
+}
+
+impl Display for Chain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "\tchain {} {{", self.name)?;
+        writeln!(
+            f,
+            "\t\ttype {} hook {} priority {}; policy {};",
+            self.chain_type, self.hook, self.priority, self.policy
+        )?;
+        for rule in &self.rules {
+            writeln!(f, "\t\t{}", rule)?;
+        }
+        writeln!(f, "\t}}")
+    }
+}
+
+impl Chain {
+    pub fn new(name: &str, chain_type: ChainType, hook: Hook, priority: i32) -> Self {
+        Chain {
+            name: name.to_string(),
+            chain_type,
+            hook,
+            priority,
+            policy: Policy::Accept,
+            rules: Vec::new(),
+        }
+    }
+
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Match {
+    Iifname(String),
+    Oifname(String),
+    CtState(Vec<CtState>),
+    TcpSyn,
+}
+
+impl Display for Match {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Match::Iifname(name) => write!(f, "iifname \"{}\"", name),
+            Match::Oifname(name) => write!(f, "oifname \"{}\"", name),
+            Match::CtState(states) => {
+                let states = states
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect::<Vec<String>>();
+                write!(f, "ct state {{ {} }}", states.join(", "))
+            }
+            Match::TcpSyn => write!(f, "tcp flags syn / syn,rst"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CtState {
+    New,
+    Established,
+    Related,
+    Invalid,
+}
+
+impl Display for CtState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CtState::New => write!(f, "new"),
+            CtState::Established => write!(f, "established"),
+            CtState::Related => write!(f, "related"),
+            CtState::Invalid => write!(f, "invalid"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Accept,
+    Drop,
+    Masquerade,
+    /// Rewrite the TCP MSS option to a fixed size
+    SetMss(u32),
+}
+
+impl Display for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Statement::Accept => write!(f, "accept"),
+            Statement::Drop => write!(f, "drop"),
+            Statement::Masquerade => write!(f, "masquerade"),
+            Statement::SetMss(size) => write!(f, "tcp option maxseg size set {}", size),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub matches: Vec<Match>,
+    pub statement: Statement,
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for m in &self.matches {
+            write!(f, "{} ", m)?;
+        }
+        write!(f, "{}", self.statement)
+    }
+}
+
+impl Rule {
+    pub fn new(statement: Statement) -> Self {
+        Rule {
+            matches: Vec::new(),
+            statement,
+        }
+    }
+
+    pub fn with(mut self, m: Match) -> Self {
+        self.matches.push(m);
+        self
+    }
+}
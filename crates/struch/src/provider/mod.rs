@@ -0,0 +1,153 @@
+use std::{fs, io};
+
+use crate::modemmanager::bearer::{Auth, BearerProperties};
+
+/// Installed by the mobile-broadband-provider-info package.
+pub const SYSTEM_DATABASE: &str = "/usr/share/mobile-broadband-provider-info/serviceproviders.xml";
+
+const BUNDLED_DATABASE: &str = include_str!("serviceproviders.xml");
+
+/// Internet APN settings of one provider.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ApnSettings {
+    pub provider: String,
+    pub apn: String,
+    pub user: String,
+    pub password: String,
+    pub auth: Option<Auth>,
+}
+
+impl ApnSettings {
+    pub fn properties(&self) -> BearerProperties {
+        BearerProperties {
+            user: self.user.clone(),
+            password: self.password.clone(),
+            allowed_auth: self.auth.into_iter().collect(),
+            ..BearerProperties::new(&self.apn)
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+struct Provider {
+    primary: bool,
+    /// MCC and MNC pairs
+    network_ids: Vec<(String, String)>,
+    apn: ApnSettings,
+}
+
+/// The mobile-broadband-provider-info database, reduced to the internet APN
+/// of each 3GPP provider.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ProviderDatabase {
+    providers: Vec<Provider>,
+}
+
+impl ProviderDatabase {
+    /// The system database when installed and valid, the bundled copy
+    /// otherwise.
+    pub fn load() -> io::Result<ProviderDatabase> {
+        fs::read_to_string(SYSTEM_DATABASE)
+            .and_then(|xml| Self::parse(&xml))
+            .or_else(|_| Self::bundled())
+    }
+
+    pub fn bundled() -> io::Result<ProviderDatabase> {
+        Self::parse(BUNDLED_DATABASE)
+    }
+
+    pub fn parse(xml: &str) -> io::Result<ProviderDatabase> {
+        let doc = roxmltree::Document::parse(xml).map_err(io::Error::other)?;
+        let child_text = |node: roxmltree::Node, name: &str| {
+            node.children()
+                .find(|n| n.has_tag_name(name))
+                .and_then(|n| n.text())
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let mut providers = Vec::new();
+        for provider in doc.descendants().filter(|n| n.has_tag_name("provider")) {
+            let Some(gsm) = provider.children().find(|n| n.has_tag_name("gsm")) else {
+                continue;
+            };
+            // an apn without usage predates the usage element and is internet
+            let Some(apn) = gsm
+                .children()
+                .filter(|n| n.has_tag_name("apn"))
+                .find(|apn| {
+                    apn.children()
+                        .filter(|n| n.has_tag_name("usage"))
+                        .all(|usage| usage.attribute("type") == Some("internet"))
+                })
+            else {
+                continue;
+            };
+            let network_ids = gsm
+                .children()
+                .filter(|n| n.has_tag_name("network-id"))
+                .filter_map(|n| Some((n.attribute("mcc")?.into(), n.attribute("mnc")?.into())))
+                .collect();
+            let auth = apn
+                .children()
+                .find(|n| n.has_tag_name("authentication"))
+                .and_then(|n| n.attribute("method")?.parse().ok());
+            providers.push(Provider {
+                primary: provider.attribute("primary") == Some("true"),
+                network_ids,
+                apn: ApnSettings {
+                    provider: child_text(provider, "name"),
+                    apn: apn.attribute("value").unwrap_or_default().to_string(),
+                    user: child_text(apn, "username"),
+                    password: child_text(apn, "password"),
+                    auth,
+                },
+            });
+        }
+        Ok(ProviderDatabase { providers })
+    }
+
+    /// Look up by MCC and MNC, preferring the primary provider of a network.
+    pub fn lookup(&self, mcc: &str, mnc: &str) -> Option<&ApnSettings> {
+        let mut matches = self
+            .providers
+            .iter()
+            .filter(|p| p.network_ids.iter().any(|(c, n)| c == mcc && n == mnc));
+        let first = matches.clone().next()?;
+        Some(&matches.find(|p| p.primary).unwrap_or(first).apn)
+    }
+
+    /// Look up by operator code, e.g. `46000`.
+    pub fn lookup_operator(&self, operator_code: &str) -> Option<&ApnSettings> {
+        if operator_code.len() < 5 || !operator_code.is_char_boundary(3) {
+            return None;
+        }
+        let (mcc, mnc) = operator_code.split_at(3);
+        self.lookup(mcc, mnc)
+    }
+
+    /// Look up by IMSI, the MNC is 2 or 3 digits so the longer one is tried
+    /// first.
+    pub fn lookup_imsi(&self, imsi: &str) -> Option<&ApnSettings> {
+        let digits = imsi.get(..6)?;
+        self.lookup_operator(digits)
+            .or_else(|| self.lookup_operator(&digits[..5]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let db = ProviderDatabase::bundled().unwrap();
+        assert_eq!(db.lookup_operator("46001").unwrap().apn, "3gnet");
+        assert_eq!(db.lookup_imsi("460001234567890").unwrap().apn, "cmnet");
+        assert_eq!(db.lookup_imsi("310410123456789").unwrap().provider, "AT&T");
+        let ee = db.lookup("234", "30").unwrap();
+        assert_eq!(ee.user, "eesecure");
+        assert_eq!(ee.auth, Some(Auth::Pap));
+        assert!(db.lookup_operator("99999").is_none());
+    }
+}
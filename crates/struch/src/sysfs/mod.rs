@@ -0,0 +1,28 @@
+mod net;
+mod qmi;
+pub use net::{Hardware, Kind};
+pub use qmi::Qmi;
+
+use std::{fs, io, path::PathBuf};
+
+static SYS_CLASS_NET: &str = "/sys/class/net";
+
+pub fn net_path(ifname: &str) -> PathBuf {
+    PathBuf::from(SYS_CLASS_NET).join(ifname)
+}
+
+pub fn read_attr(ifname: &str, attr: &str) -> io::Result<String> {
+    fs::read_to_string(net_path(ifname).join(attr)).map(|s| s.trim().to_string())
+}
+
+pub fn write_attr(ifname: &str, attr: &str, value: &str) -> io::Result<()> {
+    fs::write(net_path(ifname).join(attr), value)
+}
+
+/// Name of the kernel driver bound to the interface, e.g. `qmi_wwan`.
+pub fn driver(ifname: &str) -> io::Result<String> {
+    let link = fs::read_link(net_path(ifname).join("device/driver"))?;
+    link.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| io::Error::other(format!("No driver bound to {}", ifname)))
+}
@@ -0,0 +1,153 @@
+use std::{fmt::Display, fs, path::Path};
+
+use super::{driver, net_path, read_attr};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Wwan,
+    Wlan,
+    Ethernet,
+    Loopback,
+    Virtual,
+    #[default]
+    Unknown,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Wwan => write!(f, "wwan"),
+            Kind::Wlan => write!(f, "wlan"),
+            Kind::Ethernet => write!(f, "ethernet"),
+            Kind::Loopback => write!(f, "loopback"),
+            Kind::Virtual => write!(f, "virtual"),
+            Kind::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Drivers of net ports exposed by cellular modems.
+static WWAN_DRIVERS: &[&str] = &["qmi_wwan", "cdc_mbim", "mhi_net", "ipa"];
+
+/// Drivers shared by modems, usb ethernet adapters and phone tethering.
+static SHARED_DRIVERS: &[&str] = &["cdc_ncm", "cdc_ether"];
+
+/// Hardware details of a net interface read from sysfs.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Hardware {
+    pub driver: Option<String>,
+    /// Bus of the parent device: usb, pci, virtio, platform, ...
+    pub bus: Option<String>,
+    /// `vendor:product` of the parent usb device
+    pub usb_id: Option<String>,
+    /// Physical usb port path such as `1-1.2`
+    pub usb_path: Option<String>,
+    pub pci_slot: Option<String>,
+    /// Link speed in Mb/s
+    pub speed: Option<u32>,
+    pub duplex: Option<String>,
+    pub carrier: Option<bool>,
+    pub kind: Kind,
+}
+
+impl Hardware {
+    pub fn new(ifname: &str) -> Hardware {
+        let mut hardware = Hardware {
+            driver: driver(ifname).ok(),
+            speed: read_attr(ifname, "speed").ok().and_then(|s| s.parse().ok()),
+            duplex: read_attr(ifname, "duplex").ok().filter(|d| d != "unknown"),
+            carrier: read_attr(ifname, "carrier").ok().map(|c| c == "1"),
+            ..Default::default()
+        };
+        if let Ok(device) = fs::canonicalize(net_path(ifname).join("device")) {
+            hardware.bus = subsystem(&device);
+            for dir in device.ancestors() {
+                match subsystem(dir).as_deref() {
+                    Some("usb") if hardware.usb_id.is_none() && dir.join("idVendor").exists() => {
+                        let attr = |name| {
+                            fs::read_to_string(dir.join(name))
+                                .map(|s| s.trim().to_string())
+                                .unwrap_or_default()
+                        };
+                        hardware.usb_id =
+                            Some(format!("{}:{}", attr("idVendor"), attr("idProduct")));
+                        hardware.usb_path = file_name(dir);
+                    }
+                    Some("pci") if hardware.pci_slot.is_none() => {
+                        hardware.pci_slot = file_name(dir);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        hardware.kind = kind(ifname, &hardware);
+        hardware
+    }
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+fn subsystem(path: &Path) -> Option<String> {
+    fs::read_link(path.join("subsystem"))
+        .ok()
+        .and_then(|link| file_name(&link))
+}
+
+fn kind(ifname: &str, hardware: &Hardware) -> Kind {
+    let path = net_path(ifname);
+    let uevent = read_attr(ifname, "uevent").unwrap_or_default();
+    let devtype = uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("DEVTYPE="))
+        .unwrap_or("");
+    let link_type = read_attr(ifname, "type").unwrap_or_default();
+    if link_type == "772" {
+        return Kind::Loopback;
+    }
+    if devtype == "wlan" || path.join("wireless").exists() || path.join("phy80211").exists() {
+        return Kind::Wlan;
+    }
+    let driver = hardware.driver.as_deref().unwrap_or("");
+    let wwan_driver = WWAN_DRIVERS.contains(&driver)
+        || (SHARED_DRIVERS.contains(&driver) && has_modem_ports(&path));
+    if devtype == "wwan" || wwan_driver || path.join("qmi").exists() {
+        return Kind::Wwan;
+    }
+    if !path.join("device").exists() {
+        return Kind::Virtual;
+    }
+    if link_type == "1" {
+        return Kind::Ethernet;
+    }
+    Kind::Unknown
+}
+
+/// Whether the usb device behind the interface also exposes a tty or
+/// cdc-wdm control port, as the modems ModemManager drives do.
+fn has_modem_ports(path: &Path) -> bool {
+    let Ok(device) = fs::canonicalize(path.join("device")) else {
+        return false;
+    };
+    let Some(usb) = device.ancestors().find(|dir| dir.join("idVendor").exists()) else {
+        return false;
+    };
+    let Ok(interfaces) = fs::read_dir(usb) else {
+        return false;
+    };
+    interfaces.flatten().any(|interface| {
+        let dir = interface.path();
+        dir.join("tty").exists()
+            || dir.join("usbmisc").exists()
+            || fs::read_dir(&dir)
+                .map(|entries| {
+                    entries.flatten().any(|entry| {
+                        let name = entry.file_name().to_string_lossy().to_string();
+                        name.starts_with("ttyUSB") || name.starts_with("ttyACM")
+                    })
+                })
+                .unwrap_or(false)
+    })
+}
@@ -0,0 +1,91 @@
+use std::io;
+
+use super::{driver, net_path, read_attr, write_attr};
+use crate::iproute2::Device;
+
+static QMI_WWAN: &str = "qmi_wwan";
+
+/// The `qmi` sysfs controls of a qmi_wwan net interface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Qmi {
+    pub ifname: String,
+}
+
+impl Qmi {
+    /// Return the controls of `ifname` if it is driven by qmi_wwan.
+    ///
+    /// `drivers` is the `Generic.drivers` list reported by ModemManager,
+    /// sysfs is consulted when it does not mention qmi_wwan.
+    pub fn detect(ifname: &str, drivers: &[String]) -> Option<Qmi> {
+        let is_qmi = drivers.iter().any(|d| d == QMI_WWAN)
+            || driver(ifname).map(|d| d == QMI_WWAN).unwrap_or(false);
+        if is_qmi && net_path(ifname).join("qmi").is_dir() {
+            return Some(Qmi {
+                ifname: ifname.to_string(),
+            });
+        }
+        None
+    }
+
+    fn read_flag(&self, attr: &str) -> io::Result<bool> {
+        Ok(read_attr(&self.ifname, attr)? == "Y")
+    }
+
+    fn write_flag(&self, attr: &str, value: bool) -> io::Result<()> {
+        write_attr(&self.ifname, attr, if value { "Y" } else { "N" })
+    }
+
+    pub fn raw_ip(&self) -> io::Result<bool> {
+        self.read_flag("qmi/raw_ip")
+    }
+
+    /// The kernel only accepts the change while the link is down.
+    pub fn set_raw_ip(&self, raw_ip: bool) -> io::Result<()> {
+        self.write_flag("qmi/raw_ip", raw_ip)
+    }
+
+    pub fn pass_through(&self) -> io::Result<bool> {
+        self.read_flag("qmi/pass_through")
+    }
+
+    pub fn set_pass_through(&self, pass_through: bool) -> io::Result<()> {
+        self.write_flag("qmi/pass_through", pass_through)
+    }
+
+    /// Create the qmimux interface for `mux_id`, the link must be down.
+    pub fn add_mux(&self, mux_id: u8) -> io::Result<()> {
+        write_attr(&self.ifname, "qmi/add_mux", &mux_id.to_string())
+    }
+
+    pub fn del_mux(&self, mux_id: u8) -> io::Result<()> {
+        write_attr(&self.ifname, "qmi/del_mux", &mux_id.to_string())
+    }
+
+    /// Mux ids of the existing qmimux interfaces.
+    pub fn mux_ids(&self) -> io::Result<Vec<u8>> {
+        Ok(read_attr(&self.ifname, "qmi/add_mux")?
+            .split_whitespace()
+            .filter_map(|id| u8::from_str_radix(id.trim_start_matches("0x"), 16).ok())
+            .collect())
+    }
+
+    /// Switch the interface to raw-IP mode, taking the link down if needed.
+    ///
+    /// Nothing is touched when raw-IP is already on. Otherwise the link is
+    /// left down afterwards, callers bring it up once the addresses are
+    /// configured.
+    pub fn ensure_raw_ip(&self) -> io::Result<()> {
+        if self.raw_ip()? {
+            return Ok(());
+        }
+        Device::new(&self.ifname)?.set_down()?;
+        self.set_raw_ip(true)?;
+        if !self.raw_ip()? {
+            return Err(io::Error::other(format!(
+                "Failed to enable raw_ip on {}",
+                self.ifname
+            )));
+        }
+        Ok(())
+    }
+}
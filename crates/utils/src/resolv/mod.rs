@@ -9,6 +9,7 @@ pub struct Resolv {
     pub first_record: usize,
     pub nameserver: HashSet<String>,
     pub add_nameserver: HashSet<String>,
+    pub del_nameserver: HashSet<String>,
 }
 
 impl FromStr for Resolv {
@@ -27,8 +28,7 @@ impl Display for Resolv {
 
 impl Resolv {
     pub fn new() -> io::Result<Self> {
-        let file = std::fs::read_to_string(RESOLV_CONF)
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let file = std::fs::read_to_string(RESOLV_CONF).map_err(io::Error::other)?;
         let mut resolv = Self {
             first_record: 0,
             ..Default::default()
@@ -52,10 +52,27 @@ impl Resolv {
         }
     }
 
+    pub fn del_resolv(&mut self, ip_addr: impl Display) {
+        let ip_addr = ip_addr.to_string();
+        self.add_nameserver.remove(&ip_addr);
+        if self.nameserver.contains(&ip_addr) {
+            self.del_nameserver.insert(ip_addr);
+        }
+    }
+
     pub fn update_resolv(&mut self) -> io::Result<()> {
-        let file =
-            fs::read_to_string(RESOLV_CONF).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        let file = fs::read_to_string(RESOLV_CONF).map_err(io::Error::other)?;
         let mut lines = file.lines().map(|s| s.to_string()).collect::<Vec<String>>();
+        if !self.del_nameserver.is_empty() {
+            lines.retain(|line| {
+                let ns = line.split_whitespace().last().unwrap_or("");
+                !(line.starts_with("nameserver") && self.del_nameserver.contains(ns))
+            });
+            self.first_record = lines
+                .iter()
+                .position(|line| line.starts_with("nameserver"))
+                .unwrap_or(0);
+        }
         if self.first_record == 0 {
             self.first_record = lines.len();
         }
@@ -1,3 +1,5 @@
+use std::io;
+
 pub fn geteuid() -> u32 {
     unsafe { libc::geteuid() }
 }
@@ -5,3 +7,19 @@ pub fn geteuid() -> u32 {
 pub fn getuid() -> u32 {
     unsafe { libc::getuid() }
 }
+
+pub fn is_root() -> bool {
+    getuid() == 0 || geteuid() == 0
+}
+
+/// Set the system clock to `secs` since the epoch, needs CAP_SYS_TIME.
+pub fn set_time(secs: i64) -> io::Result<()> {
+    let time = libc::timespec {
+        tv_sec: secs as libc::time_t,
+        tv_nsec: 0,
+    };
+    if unsafe { libc::clock_settime(libc::CLOCK_REALTIME, &time) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}